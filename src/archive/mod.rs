@@ -0,0 +1,294 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use anyhow::Result;
+use log::warn;
+
+use crate::league::{self, MatchResult};
+use crate::protocol::Color;
+use crate::scoreboard::{Card, PeriodScore, Player, ScoreEvent, Side, Substitution};
+
+/// Directory finished matches are kept in, one JSON file each, next to
+/// config.yaml
+pub const MATCHES_DIR: &str = "matches";
+
+/// A team as it was at the end of a match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedTeam {
+    pub name: String,
+    #[serde(default)]
+    pub short_name: Option<String>,
+    /// Registry ID, when the team was picked from the registry
+    #[serde(default)]
+    pub team_id: Option<String>,
+    pub color: Color,
+    pub score: u16,
+    /// Matchday squad, when one was entered
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roster: Vec<Player>,
+}
+
+/// Everything kept about a finished match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedMatch {
+    pub id: String,
+    /// Pitch the match was played on
+    pub pitch: String,
+    #[serde(default)]
+    pub competition: Option<String>,
+    pub profile: String,
+    /// Length of each period
+    #[serde(default = "default_period_minutes")]
+    pub period_minutes: u8,
+    pub home: ArchivedTeam,
+    pub away: ArchivedTeam,
+    /// Score when the half-time break began
+    #[serde(default)]
+    pub half_time: Option<PeriodScore>,
+    /// Match clock when the match was finished
+    pub minutes: u8,
+    pub seconds: u8,
+    /// Scheduled kick-off, for matches set up from a fixture
+    #[serde(default)]
+    pub kick_off: Option<NaiveDateTime>,
+    /// When the match clock was first started
+    #[serde(default)]
+    pub started_at: Option<DateTime<Local>>,
+    pub finished_at: DateTime<Local>,
+    /// Scores in the order they happened
+    pub events: Vec<ScoreEvent>,
+    /// Substitutions and temporary replacements
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub substitutions: Vec<Substitution>,
+    /// Cards shown
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<Card>,
+}
+
+fn default_period_minutes() -> u8 {
+    40
+}
+
+impl ArchivedMatch {
+    /// When the match was played, see [`played_at`]
+    pub fn played_at(&self) -> NaiveDateTime {
+        played_at(self.kick_off, self.started_at, self.finished_at)
+    }
+
+    /// Each team's try scorers with their number of tries, in the order
+    /// they first scored
+    pub fn try_scorers(&self, side: Side) -> Vec<(Player, u16)> {
+        let mut scorers: Vec<(Player, u16)> = Vec::new();
+        let tries = self.events.iter()
+            .filter(|event| event.team == side && league::TRY_ACTIONS.contains(&event.action.as_str()));
+        for player in tries.filter_map(|event| event.player.as_ref()) {
+            match scorers.iter_mut().find(|(scorer, _)| scorer.number == player.number) {
+                Some((_, count)) => *count += 1,
+                None => scorers.push((player.clone(), 1)),
+            }
+        }
+        scorers
+    }
+
+    /// The match's result for the league table
+    pub fn result(&self) -> MatchResult {
+        MatchResult {
+            id: self.id.clone(),
+            competition: self.competition.clone(),
+            home: self.home.name.clone(),
+            away: self.away.name.clone(),
+            home_score: self.home.score,
+            away_score: self.away.score,
+            home_tries: league::tries(&self.events, Side::Home),
+            away_tries: league::tries(&self.events, Side::Away),
+            played_at: self.played_at(),
+        }
+    }
+
+    pub fn summary(&self) -> MatchSummary {
+        MatchSummary {
+            id: self.id.clone(),
+            pitch: self.pitch.clone(),
+            competition: self.competition.clone(),
+            home: self.home.name.clone(),
+            away: self.away.name.clone(),
+            home_score: self.home.score,
+            away_score: self.away.score,
+            played_at: self.played_at(),
+            finished_at: self.finished_at,
+        }
+    }
+}
+
+/// When a match is taken to have been played: its kick-off, or when the
+/// clock was started, or failing both the given time
+pub fn played_at(kick_off: Option<NaiveDateTime>, started_at: Option<DateTime<Local>>, otherwise: DateTime<Local>) -> NaiveDateTime {
+    kick_off
+        .or_else(|| started_at.map(|started| started.naive_local()))
+        .unwrap_or_else(|| otherwise.naive_local())
+}
+
+/// An archived match without its timeline, for listing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MatchSummary {
+    pub id: String,
+    pub pitch: String,
+    pub competition: Option<String>,
+    pub home: String,
+    pub away: String,
+    pub home_score: u16,
+    pub away_score: u16,
+    pub played_at: NaiveDateTime,
+    pub finished_at: DateTime<Local>,
+}
+
+/// Finished matches persisted to a directory of JSON files
+pub struct MatchArchive {
+    dir: PathBuf,
+    matches: Mutex<Vec<ArchivedMatch>>,
+}
+
+impl MatchArchive {
+    /// Load the archived matches from a directory, starting empty if it
+    /// doesn't exist
+    pub fn load(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let mut matches = Vec::new();
+        if dir.exists() {
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|extension| extension == "json") {
+                    // One bad file shouldn't keep the server from starting
+                    match Self::read(&path) {
+                        Ok(archived) => matches.push(archived),
+                        Err(e) => warn!("Skipping archived match {}: {:#}", path.display(), e),
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            dir,
+            matches: Mutex::new(matches),
+        })
+    }
+
+    fn read(path: &Path) -> Result<ArchivedMatch> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// All matches, most recently played first
+    pub async fn list(&self) -> Vec<ArchivedMatch> {
+        let mut matches = self.matches.lock().await.clone();
+        matches.sort_by_key(|archived| Reverse(archived.played_at()));
+        matches
+    }
+
+    /// Look up a match by ID
+    pub async fn get(&self, id: &str) -> Option<ArchivedMatch> {
+        self.matches.lock().await.iter().find(|archived| archived.id == id).cloned()
+    }
+
+    /// Archive a match, replacing it if it was finished before
+    pub async fn store(&self, archived: ArchivedMatch) -> Result<()> {
+        let mut matches = self.matches.lock().await;
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.dir.join(format!("{}.json", archived.id)), serde_json::to_string_pretty(&archived)?)?;
+        match matches.iter_mut().find(|existing| existing.id == archived.id) {
+            Some(existing) => *existing = archived,
+            None => matches.push(archived),
+        }
+        Ok(())
+    }
+}
+
+/// One row per match, for spreadsheets and the club website
+pub fn matches_csv(matches: &[ArchivedMatch]) -> Result<String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(["id", "date", "competition", "home", "away", "home_score", "away_score", "home_tries", "away_tries", "pitch"])?;
+    for archived in matches {
+        let result = archived.result();
+        writer.write_record([
+            archived.id.clone(),
+            result.played_at.format("%Y-%m-%d %H:%M").to_string(),
+            result.competition.unwrap_or_default(),
+            result.home,
+            result.away,
+            result.home_score.to_string(),
+            result.away_score.to_string(),
+            result.home_tries.to_string(),
+            result.away_tries.to_string(),
+            archived.pitch.clone(),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// One row per score of a match, with the running score after it
+pub fn timeline_csv(archived: &ArchivedMatch) -> Result<String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(["minute", "second", "team", "action", "points", "number", "player", "home_score", "away_score"])?;
+    let (mut home, mut away) = (0u16, 0u16);
+    for event in &archived.events {
+        let team = match event.team {
+            Side::Home => {
+                home += event.points;
+                &archived.home.name
+            }
+            Side::Away => {
+                away += event.points;
+                &archived.away.name
+            }
+        };
+        writer.write_record([
+            event.minute.to_string(),
+            event.second.to_string(),
+            team.clone(),
+            event.label.clone(),
+            event.points.to_string(),
+            event.player.as_ref().map(|player| player.number.to_string()).unwrap_or_default(),
+            event.player.as_ref().map(|player| player.name.clone()).unwrap_or_default(),
+            home.to_string(),
+            away.to_string(),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::scoreboard::ScoreboardController;
+
+    #[tokio::test]
+    async fn test_finished_matches_are_archived() {
+        let dir = std::env::temp_dir().join(format!("matches-{}", std::process::id()));
+        let archive = MatchArchive::load(&dir).unwrap();
+
+        let controller = ScoreboardController::new(true, Config::default());
+        controller.set_teams("HRUFC".to_string(), "Henley Hawks".to_string()).await.unwrap();
+        controller.start_timer().await.unwrap();
+        controller.score_action("home", "try").await.unwrap();
+        controller.score_action("away", "penalty").await.unwrap();
+        let archived = controller.finish_match("main").await;
+        assert!(!controller.get_state().await.timer_running);
+        archive.store(archived.clone()).await.unwrap();
+        // Finishing again replaces the archived match
+        archive.store(archived.clone()).await.unwrap();
+
+        fs::write(dir.join("corrupt.json"), "{").unwrap();
+        let reloaded = MatchArchive::load(&dir).unwrap();
+        assert_eq!(reloaded.list().await, vec![archived.clone()]);
+        assert_eq!(archived.result().home_tries, 1);
+
+        let csv = timeline_csv(&archived).unwrap();
+        assert_eq!(csv.lines().nth(2), Some("0,0,Henley Hawks,Penalty,3,,,5,3"));
+        let csv = matches_csv(std::slice::from_ref(&archived)).unwrap();
+        assert!(csv.lines().nth(1).unwrap().contains(",HRUFC,Henley Hawks,5,3,1,0,main"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,30 @@
+use std::env;
+
+use scoreboard_server::mock::MockCard;
+
+/// `mock-card [--address ADDRESS] [--card-id ID]`
+///
+/// Pretends to be a CPower control card so the server can be run without
+/// hardware. Window changes are logged as they arrive.
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let mut address = "127.0.0.1:5200".to_string();
+    let mut card_id: u8 = 1;
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+        match arg.as_str() {
+            "--address" => address = value.clone(),
+            "--card-id" => card_id = value.parse()?,
+            _ => return Err(format!("Unknown option: {}", arg).into()),
+        }
+    }
+
+    let listener = tokio::net::TcpListener::bind(&address).await?;
+    MockCard::new(card_id).serve(listener).await?;
+    Ok(())
+}
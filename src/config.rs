@@ -1,13 +1,236 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
-use anyhow::Result;
+use anyhow::{Context, Result, bail};
+
+use crate::protocol::{Color, ScoreboardLayout, discovery, effects, windows};
+use crate::rules::{CustomAction, RuleProfile, Side};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub server: ServerConfig,
     pub scoreboard: ScoreboardConfig,
     pub rugby: RugbyConfig,
+    /// Boards mirroring the same match, in place of the `scoreboard`
+    /// section's board. When empty, that section describes the only board.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub boards: Vec<BoardConfig>,
+    /// Independent matches hosted by this server, each with its own boards.
+    /// When empty, a single pitch uses the boards above.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pitches: Vec<PitchConfig>,
+    /// How text too wide for its window is fitted
+    #[serde(default)]
+    pub text_fit: TextFitConfig,
+    /// Rule profile used when a match doesn't pick one
+    #[serde(default = "default_profile")]
+    pub default_profile: String,
+    /// Rule profiles added to (or replacing) the built-in ones
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<RuleProfile>,
+    /// Score actions added to the profiles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_actions: Vec<CustomAction>,
+    /// Rules of competitions named in fixtures
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub competitions: Vec<CompetitionConfig>,
+    /// Countdown for conversion attempts
+    #[serde(default)]
+    pub shot_clock: ShotClockConfig,
+    /// How league points are awarded for results
+    #[serde(default)]
+    pub league: LeagueConfig,
+    /// What the board shows during the half-time break
+    #[serde(default)]
+    pub half_time: HalfTimeConfig,
+    /// Showing who scored in place of the team's name
+    #[serde(default)]
+    pub scorer_flash: ScorerFlashConfig,
+    /// Time allowed off the field for temporary replacements
+    #[serde(default)]
+    pub replacements: ReplacementConfig,
+    /// Sponsor messages looped on the board before kick-off, at half time
+    /// and after the match
+    #[serde(default)]
+    pub sponsors: SponsorConfig,
+    /// Animations played on the board when an action is scored
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub celebrations: Vec<CelebrationConfig>,
+}
+
+/// Frames played in a window, or on the whole board, when an action is
+/// scored, before the board goes back to the scores
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CelebrationConfig {
+    /// Name of a rule profile action, such as "try"
+    pub action: String,
+    /// Only when this team scores, either team when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<Side>,
+    /// Name of the window, the whole board when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub window: Option<String>,
+    pub frames: Vec<CelebrationFrame>,
+}
+
+/// Text shown for a moment of a celebration. `{team}` is replaced by the
+/// scoring team's name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CelebrationFrame {
+    pub text: String,
+    #[serde(default = "default_frame_seconds")]
+    pub seconds: u16,
+    /// Name of a display effect, instant when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect: Option<String>,
+    /// Colour of the text, yellow when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+}
+
+fn default_frame_seconds() -> u16 {
+    1
+}
+
+/// Sponsor playlist shown between play
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SponsorConfig {
+    /// Off unless turned on
+    pub enabled: bool,
+    /// Items shown in turn, starting again after the last one
+    pub playlist: Vec<SponsorItem>,
+}
+
+/// A sponsor's message or logo, shown on the whole board for a number of
+/// seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SponsorItem {
+    pub sponsor: String,
+    /// Message shown, or the logo's description in the log
+    pub text: String,
+    /// Path of a PNG or BMP logo shown in place of the text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    #[serde(default = "default_sponsor_seconds")]
+    pub seconds: u16,
+    /// Name of a display effect, instant when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effect: Option<String>,
+    /// Colour of the text, yellow when omitted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+}
+
+fn default_sponsor_seconds() -> u16 {
+    10
+}
+
+/// Minutes of match clock a player replaced for a head injury assessment
+/// or a blood injury has to return
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReplacementConfig {
+    pub hia_minutes: u8,
+    pub blood_minutes: u8,
+}
+
+impl Default for ReplacementConfig {
+    fn default() -> Self {
+        Self {
+            hia_minutes: 12,
+            blood_minutes: 15,
+        }
+    }
+}
+
+/// Scorer shown in the team's name window, such as "TRY 11 SMITH", when a
+/// score is given a shirt number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScorerFlashConfig {
+    pub enabled: bool,
+    pub seconds: u8,
+}
+
+impl Default for ScorerFlashConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            seconds: 5,
+        }
+    }
+}
+
+/// Board display during the half-time break
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HalfTimeConfig {
+    /// Alternate the scores with an "HT" label
+    pub alternate: bool,
+    /// Seconds each is shown for
+    pub seconds: u8,
+}
+
+impl Default for HalfTimeConfig {
+    fn default() -> Self {
+        Self {
+            alternate: false,
+            seconds: 5,
+        }
+    }
+}
+
+/// League points for a result, with at most one try bonus and one losing
+/// bonus point per match
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LeagueConfig {
+    pub win_points: u8,
+    pub draw_points: u8,
+    pub loss_points: u8,
+    /// Tries that earn a bonus point, none for no try bonus
+    pub try_bonus_tries: Option<u16>,
+    /// Count only the tries scored beyond the opponent's for the try bonus
+    pub try_bonus_relative: bool,
+    /// Widest losing margin that earns a bonus point, none for no losing
+    /// bonus
+    pub losing_bonus_margin: Option<u16>,
+}
+
+impl Default for LeagueConfig {
+    fn default() -> Self {
+        Self {
+            win_points: 4,
+            draw_points: 2,
+            loss_points: 0,
+            try_bonus_tries: Some(4),
+            try_bonus_relative: false,
+            losing_bonus_margin: Some(7),
+        }
+    }
+}
+
+/// Countdown shown after a try while the conversion is taken
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShotClockConfig {
+    /// Start the countdown automatically after a try
+    pub enabled: bool,
+    pub seconds: u16,
+}
+
+impl Default for ShotClockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seconds: 90,
+        }
+    }
+}
+
+fn default_profile() -> String {
+    "union".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,11 +247,122 @@ pub struct ScoreboardConfig {
     pub reconnect_interval_seconds: u64,
 }
 
+/// A physical board driven by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardConfig {
+    pub name: String,
+    pub address: String,
+    pub card_id: u8,
+    #[serde(default = "ScoreboardLayout::standard_224x32")]
+    pub layout: ScoreboardLayout,
+}
+
+/// A pitch running its own match
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PitchConfig {
+    pub id: String,
+    pub name: String,
+    pub boards: Vec<BoardConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RugbyConfig {
     pub try_points: u16,
     pub conversion_points: u16,
     pub penalty_points: u16,
+    /// Length of each half of a union match
+    #[serde(default = "default_period_minutes")]
+    pub period_minutes: u8,
+}
+
+fn default_period_minutes() -> u8 {
+    40
+}
+
+/// A competition and the rules its matches are played under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompetitionConfig {
+    pub name: String,
+    /// Rule profile, `default_profile` when omitted
+    #[serde(default)]
+    pub profile: Option<String>,
+    /// Length of each period when it differs from the profile's
+    #[serde(default)]
+    pub period_minutes: Option<u8>,
+    /// Points for a try, conversion or penalty when they differ from the
+    /// profile's, as competitions set them before there were profiles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub try_points: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conversion_points: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub penalty_points: Option<u16>,
+}
+
+impl CompetitionConfig {
+    /// Give a profile's actions the competition's points
+    pub fn apply(&self, profile: &mut RuleProfile) {
+        let points = [("try", self.try_points), ("conversion", self.conversion_points), ("penalty", self.penalty_points)];
+        for (name, points) in points {
+            let action = profile.actions.iter_mut().find(|action| action.name == name);
+            if let (Some(action), Some(points)) = (action, points) {
+                action.points = points;
+            }
+        }
+    }
+}
+
+/// What to do with text that is too wide for its window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FitPolicy {
+    /// Leave it for the card to cut off
+    #[default]
+    Clip,
+    /// Use the largest smaller font that fits, scrolling if none does
+    Shrink,
+    /// Use the team's short name, abbreviating it if there is none or it
+    /// doesn't fit either
+    ShortName,
+    /// Scroll the text through the window
+    Scroll,
+}
+
+/// Fit policy for each window of the layout
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextFitConfig {
+    pub home_name: FitPolicy,
+    pub home_score: FitPolicy,
+    pub away_name: FitPolicy,
+    pub away_score: FitPolicy,
+    pub timer: FitPolicy,
+}
+
+impl Default for TextFitConfig {
+    fn default() -> Self {
+        Self {
+            home_name: FitPolicy::Shrink,
+            home_score: FitPolicy::Clip,
+            away_name: FitPolicy::Shrink,
+            away_score: FitPolicy::Clip,
+            timer: FitPolicy::Clip,
+        }
+    }
+}
+
+impl TextFitConfig {
+    /// Policy for the window with the given ID
+    pub fn policy(&self, window_id: u8) -> FitPolicy {
+        match window_id {
+            windows::HOME_NAME => self.home_name,
+            windows::HOME_SCORE => self.home_score,
+            windows::AWAY_NAME => self.away_name,
+            windows::AWAY_SCORE => self.away_score,
+            windows::TIMER => self.timer,
+            _ => FitPolicy::Clip,
+        }
+    }
 }
 
 impl Default for Config {
@@ -48,7 +382,22 @@ impl Default for Config {
                 try_points: 5,
                 conversion_points: 2,
                 penalty_points: 3,
+                period_minutes: 40,
             },
+            boards: Vec::new(),
+            pitches: Vec::new(),
+            text_fit: TextFitConfig::default(),
+            default_profile: default_profile(),
+            profiles: Vec::new(),
+            custom_actions: Vec::new(),
+            competitions: Vec::new(),
+            shot_clock: ShotClockConfig::default(),
+            league: LeagueConfig::default(),
+            half_time: HalfTimeConfig::default(),
+            scorer_flash: ScorerFlashConfig::default(),
+            replacements: ReplacementConfig::default(),
+            sponsors: SponsorConfig::default(),
+            celebrations: Vec::new(),
         }
     }
 }
@@ -60,6 +409,7 @@ impl Config {
         if Path::new(config_path).exists() {
             let config_content = fs::read_to_string(config_path)?;
             let config: Config = serde_yaml::from_str(&config_content)?;
+            config.validate().with_context(|| format!("Invalid {}", config_path))?;
             log::info!("Loaded configuration from {}", config_path);
             Ok(config)
         } else {
@@ -70,6 +420,179 @@ impl Config {
         }
     }
     
+    /// Check what can't be checked while parsing, so mistakes are found at
+    /// startup rather than mid-match
+    pub fn validate(&self) -> Result<()> {
+        let profiles = self.profiles();
+        let known = |name: &str| profiles.iter().any(|profile| profile.name == name);
+        if !known(&self.default_profile) {
+            bail!("Unknown default_profile '{}'", self.default_profile);
+        }
+        // A card with no time to serve would never let the player back on
+        for profile in &profiles {
+            if let Some(sin_bin) = profile.sin_bins.iter().find(|sin_bin| sin_bin.minutes == 0) {
+                bail!("The '{}' card in profile {} has no minutes", sin_bin.card, profile.name);
+            }
+            if profile.red_replacement_minutes == Some(0) {
+                bail!("Profile {} has no minutes before a 20-minute red can be replaced", profile.name);
+            }
+        }
+        for competition in &self.competitions {
+            if let Some(profile) = competition.profile.as_deref().filter(|name| !known(name)) {
+                bail!("Unknown profile '{}' for competition {}", profile, competition.name);
+            }
+        }
+        // Pitch IDs are a segment of the API path
+        for (index, pitch) in self.pitches.iter().enumerate() {
+            if pitch.id.is_empty() || !pitch.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                bail!("Pitch ID '{}' has to be letters, digits, '-' or '_'", pitch.id);
+            }
+            if self.pitches[..index].iter().any(|other| other.id == pitch.id) {
+                bail!("Pitch ID '{}' is used twice", pitch.id);
+            }
+        }
+        // Without boards of its own a pitch would fall back to the
+        // `scoreboard` section's card, which another pitch may be driving.
+        // Boards are looked up by name, so a second one of a name couldn't
+        // be reached.
+        for pitch in self.pitches() {
+            if pitch.boards.is_empty() {
+                bail!("Pitch {} has no boards", pitch.id);
+            }
+            for (index, board) in pitch.boards.iter().enumerate() {
+                if pitch.boards[..index].iter().any(|other| other.name == board.name) {
+                    bail!("Board name '{}' is used twice on pitch {}", board.name, pitch.id);
+                }
+            }
+        }
+        for celebration in &self.celebrations {
+            let name = &celebration.action;
+            if celebration.frames.is_empty() {
+                bail!("The {} celebration has no frames", name);
+            }
+            if let Some(window) = celebration.window.as_deref() {
+                let id = windows::by_name(window)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown window '{}' for the {} celebration", window, name))?;
+                let mut boards = self.pitches().into_iter().flat_map(|pitch| pitch.boards);
+                if let Some(board) = boards.find(|board| board.layout.window(id).is_none()) {
+                    bail!("Board {} has no {} window for the {} celebration", board.name, window, name);
+                }
+            }
+            for frame in &celebration.frames {
+                if frame.seconds == 0 {
+                    bail!("A frame of the {} celebration has to be shown for at least a second", name);
+                }
+                if let Some(effect) = frame.effect.as_deref().filter(|effect| effects::by_name(effect).is_none()) {
+                    bail!("Unknown effect '{}' for the {} celebration", effect, name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Boards to drive, falling back to the single `scoreboard` section
+    pub fn boards(&self) -> Vec<BoardConfig> {
+        if !self.boards.is_empty() {
+            return self.boards.clone();
+        }
+        vec![BoardConfig {
+            name: "main".to_string(),
+            address: self.scoreboard.address.clone(),
+            card_id: self.scoreboard.card_id,
+            layout: ScoreboardLayout::standard_224x32(),
+        }]
+    }
+
+    /// Pitches to host, falling back to a single pitch using `boards()`
+    pub fn pitches(&self) -> Vec<PitchConfig> {
+        if !self.pitches.is_empty() {
+            return self.pitches.clone();
+        }
+        vec![PitchConfig {
+            id: "main".to_string(),
+            name: "Main Pitch".to_string(),
+            boards: self.boards(),
+        }]
+    }
+
+    /// Built-in rule profiles followed by those from the config, a config
+    /// profile replacing a built-in one of the same name, with the custom
+    /// actions added
+    pub fn profiles(&self) -> Vec<RuleProfile> {
+        let mut profiles = RuleProfile::builtin(&self.rugby);
+        for custom in &self.profiles {
+            match profiles.iter_mut().find(|profile| profile.name == custom.name) {
+                Some(profile) => *profile = custom.clone(),
+                None => profiles.push(custom.clone()),
+            }
+        }
+        for custom in &self.custom_actions {
+            profiles.iter_mut()
+                .filter(|profile| custom.profile.as_ref().is_none_or(|name| *name == profile.name))
+                .for_each(|profile| profile.add_action(custom.action.clone()));
+        }
+        profiles
+    }
+
+    /// Look up a rule profile by name
+    pub fn profile(&self, name: &str) -> Option<RuleProfile> {
+        self.profiles().into_iter().find(|profile| profile.name == name)
+    }
+
+    /// Look up a competition by name, ignoring case
+    pub fn competition(&self, name: &str) -> Option<&CompetitionConfig> {
+        self.competitions.iter().find(|competition| competition.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Configuration as seen by the controller of a single pitch
+    pub fn for_pitch(&self, pitch: &PitchConfig) -> Config {
+        Config {
+            boards: pitch.boards.clone(),
+            pitches: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Point a board at a new control card. `pitch` and `board` default to
+    /// the first pitch and its first board. Returns the name of the board
+    /// that was changed.
+    pub fn set_board_target(&mut self, pitch: Option<&str>, board: Option<&str>, address: String, card_id: u8) -> Result<String> {
+        if card_id == discovery::BROADCAST_CARD_ID {
+            bail!("Card ID {:#x} is the broadcast ID, not a card's own; query the card for its ID first", card_id);
+        }
+        let boards = if self.pitches.is_empty() {
+            if let Some(id) = pitch.filter(|id| *id != "main") {
+                anyhow::bail!("Unknown pitch: {}", id);
+            }
+            if self.boards.is_empty() {
+                if let Some(name) = board.filter(|name| *name != "main") {
+                    anyhow::bail!("Unknown board: {}", name);
+                }
+                self.scoreboard.address = address;
+                self.scoreboard.card_id = card_id;
+                return Ok("main".to_string());
+            }
+            &mut self.boards
+        } else {
+            let entry = match pitch {
+                Some(id) => self.pitches.iter_mut().find(|p| p.id == id)
+                    .ok_or_else(|| anyhow::anyhow!("Unknown pitch: {}", id))?,
+                None => &mut self.pitches[0],
+            };
+            &mut entry.boards
+        };
+
+        let entry = match board {
+            Some(name) => boards.iter_mut().find(|b| b.name == name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown board: {}", name))?,
+            None => boards.first_mut()
+                .ok_or_else(|| anyhow::anyhow!("No boards configured"))?,
+        };
+        entry.address = address;
+        entry.card_id = card_id;
+        Ok(entry.name.clone())
+    }
+
     pub fn save(&self) -> Result<()> {
         let config_content = serde_yaml::to_string(self)?;
         fs::write("config.yaml", config_content)?;
@@ -81,4 +604,175 @@ impl Config {
         *self = new_config;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_board_from_scoreboard_section() {
+        let config = Config::default();
+        let boards = config.boards();
+        assert_eq!(boards.len(), 1);
+        assert_eq!(boards[0].name, "main");
+        assert_eq!(boards[0].address, "192.168.1.100:5200");
+    }
+
+    #[test]
+    fn test_boards_list_with_layout() {
+        let yaml = r#"
+server:
+  web_port: 3030
+  simulation_mode: true
+scoreboard:
+  address: "192.168.1.100:5200"
+  card_id: 1
+  connection_timeout_seconds: 5
+  reconnect_interval_seconds: 30
+rugby:
+  try_points: 5
+  conversion_points: 2
+  penalty_points: 3
+boards:
+  - name: main
+    address: "192.168.1.100:5200"
+    card_id: 1
+  - name: clubhouse
+    address: "192.168.1.101:5200"
+    card_id: 2
+    layout:
+      home_name: { x: 0, y: 0, width: 64, height: 16 }
+      home_score: { x: 64, y: 0, width: 32, height: 16 }
+      away_name: { x: 0, y: 16, width: 64, height: 16 }
+      away_score: { x: 64, y: 16, width: 32, height: 16 }
+      timer: { x: 96, y: 0, width: 64, height: 32 }
+"#;
+        let mut config: Config = serde_yaml::from_str(yaml).unwrap();
+        let boards = config.boards();
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards[0].layout.timer.x, 128);
+        assert_eq!(boards[1].layout.timer.width, 64);
+
+        let name = config.set_board_target(None, Some("clubhouse"), "10.0.0.5:5200".to_string(), 9).unwrap();
+        assert_eq!(name, "clubhouse");
+        assert_eq!(config.boards[1].card_id, 9);
+        assert!(config.set_board_target(None, Some("missing"), "10.0.0.6:5200".to_string(), 3).is_err());
+        assert!(config.set_board_target(None, Some("clubhouse"), "10.0.0.6:5200".to_string(), 0xff).is_err());
+    }
+
+    #[test]
+    fn test_config_profiles_replace_builtin_ones() {
+        let config: Config = serde_yaml::from_str(r#"
+server: { web_port: 3030, simulation_mode: true }
+scoreboard: { address: "127.0.0.1:5200", card_id: 1, connection_timeout_seconds: 5, reconnect_interval_seconds: 30 }
+rugby: { try_points: 5, conversion_points: 2, penalty_points: 3 }
+profiles:
+  - name: tag
+    label: Junior Tag
+    periods: 2
+    period_minutes: 15
+    actions: [{ name: try, label: Try, points: 1 }]
+  - name: touch
+    label: Touch
+    periods: 2
+    period_minutes: 20
+    actions: [{ name: touchdown, label: Touchdown, points: 1 }]
+custom_actions:
+  - { profile: union, name: goal_from_mark, label: Goal from Mark, points: 3 }
+  - { name: technical, label: Technical Point, points: 1 }
+"#).unwrap();
+
+        assert_eq!(config.default_profile, "union");
+        assert_eq!(config.profile("union").unwrap().action("try").unwrap().points, 5);
+        assert_eq!(config.profile("tag").unwrap().period_minutes, 15);
+        assert!(config.profile("touch").unwrap().action("technical").is_some());
+        assert_eq!(config.profiles().len(), 7);
+
+        let union = config.profile("union").unwrap();
+        assert_eq!(union.action("goal_from_mark").unwrap().points, 3);
+        assert_eq!(union.action("penalty_try").unwrap().points, 7);
+        assert!(config.profile("league").unwrap().action("goal_from_mark").is_none());
+        assert!(config.validate().is_ok());
+        assert!(Config { default_profile: "netball".to_string(), ..config.clone() }.validate().is_err());
+
+        let mut zero_minutes = config.clone();
+        zero_minutes.profiles[0].sin_bins.push(crate::rules::SinBin { card: "orange".to_string(), minutes: 0 });
+        assert!(zero_minutes.validate().is_err());
+
+        let celebration = |window: &str, effect: &str, seconds: u16| CelebrationConfig {
+            action: "try".to_string(),
+            team: Some(Side::Home),
+            window: Some(window.to_string()),
+            frames: vec![CelebrationFrame { text: "TRY!".to_string(), seconds, effect: Some(effect.to_string()), color: None }],
+        };
+        let celebrating = |celebration| Config { celebrations: vec![celebration], ..config.clone() };
+        assert!(celebrating(celebration("timer", "flicker", 2)).validate().is_ok());
+        assert!(celebrating(celebration("shot_clock", "flicker", 2)).validate().is_err());
+        assert!(celebrating(celebration("timer", "wobble", 2)).validate().is_err());
+        assert!(celebrating(celebration("timer", "flicker", 0)).validate().is_err());
+    }
+
+    #[test]
+    fn test_competitions_keep_their_points() {
+        // As competitions were written before rule profiles
+        let competition: CompetitionConfig = serde_yaml::from_str(
+            "{ name: Vets, try_points: 4, conversion_points: 2, penalty_points: 2, period_minutes: 30 }",
+        ).unwrap();
+        let mut union = Config::default().profile("union").unwrap();
+        competition.apply(&mut union);
+        assert_eq!(union.action("try").unwrap().points, 4);
+        assert_eq!(union.action("penalty").unwrap().points, 2);
+        assert_eq!(union.action("drop_goal").unwrap().points, 3);
+        assert_eq!(competition.period_minutes, Some(30));
+
+        let competitions = vec![CompetitionConfig { profile: Some("netball".to_string()), ..competition }];
+        assert!(Config { competitions, ..Config::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn test_pitches_have_own_boards() {
+        let mut config = Config::default();
+        config.pitches = vec![
+            PitchConfig {
+                id: "pitch1".to_string(),
+                name: "Pitch 1".to_string(),
+                boards: config.boards(),
+            },
+            PitchConfig {
+                id: "pitch2".to_string(),
+                name: "Pitch 2".to_string(),
+                boards: vec![BoardConfig {
+                    name: "main".to_string(),
+                    address: "192.168.1.102:5200".to_string(),
+                    card_id: 3,
+                    layout: ScoreboardLayout::standard_224x32(),
+                }],
+            },
+        ];
+
+        let pitches = config.pitches();
+        assert_eq!(pitches.len(), 2);
+        let pitch_config = config.for_pitch(&pitches[1]);
+        assert_eq!(pitch_config.boards()[0].address, "192.168.1.102:5200");
+        assert!(pitch_config.pitches.is_empty());
+
+        assert!(config.validate().is_ok());
+        for id in ["pitch1", "", "pitch/2"] {
+            let mut invalid = config.clone();
+            invalid.pitches[1].id = id.to_string();
+            assert!(invalid.validate().is_err(), "{:?}", id);
+        }
+        let mut no_boards = config.clone();
+        no_boards.pitches[1].boards.clear();
+        assert!(no_boards.validate().is_err());
+        let mut same_name = config.clone();
+        let board = same_name.pitches[1].boards[0].clone();
+        same_name.pitches[1].boards.push(board);
+        assert!(same_name.validate().is_err());
+
+        config.set_board_target(Some("pitch2"), None, "10.0.0.7:5200".to_string(), 4).unwrap();
+        assert_eq!(config.pitches[1].boards[0].card_id, 4);
+        assert!(config.set_board_target(Some("pitch3"), None, "10.0.0.8:5200".to_string(), 5).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::fixtures::Fixture;
+
+/// Parse fixtures from a spreadsheet export. The header row names the
+/// columns, in any order and case: `home`, `away`, either `date` and
+/// `time` or `kick_off`, and optionally `competition`, `period_minutes`
+/// and `venue`. Other columns are ignored.
+pub fn parse(content: &str) -> Result<Vec<Fixture>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .trim(::csv::Trim::All)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let headers: Vec<String> = reader.headers()?
+        .iter()
+        .map(|header| header.to_ascii_lowercase().replace(' ', "_"))
+        .collect();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+
+    let home = column("home").ok_or_else(|| anyhow!("Missing 'home' column"))?;
+    let away = column("away").ok_or_else(|| anyhow!("Missing 'away' column"))?;
+    let kick_off = column("kick_off");
+    let date = column("date");
+    let time = column("time");
+    if kick_off.is_none() && date.is_none() {
+        bail!("Missing 'date' or 'kick_off' column");
+    }
+    let competition = column("competition");
+    let period_minutes = column("period_minutes");
+    let venue = column("venue");
+
+    let mut fixtures = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        let record = record?;
+        let line = index + 2;
+        let field = |column: Option<usize>| {
+            column.and_then(|column| record.get(column)).filter(|value| !value.is_empty())
+        };
+
+        let (Some(home), Some(away)) = (field(Some(home)), field(Some(away))) else {
+            // Blank rows and rows without both teams, such as byes
+            continue;
+        };
+
+        let kick_off = match field(kick_off) {
+            Some(kick_off) => parse_date_time(kick_off),
+            None => {
+                let date = field(date).ok_or_else(|| anyhow!("No date"));
+                date.and_then(|date| Ok(parse_date(date)?.and_time(parse_time(field(time))?)))
+            }
+        }.with_context(|| format!("Line {}", line))?;
+
+        let mut fixture = Fixture::new(home.to_string(), away.to_string(), kick_off);
+        fixture.competition = field(competition).map(str::to_string);
+        fixture.period_minutes = field(period_minutes)
+            .map(|minutes| minutes.parse().with_context(|| format!("Line {}: invalid period length '{}'", line, minutes)))
+            .transpose()?;
+        fixture.venue = field(venue).map(str::to_string);
+        fixtures.push(fixture);
+    }
+    Ok(fixtures)
+}
+
+fn parse_date_time(value: &str) -> Result<NaiveDateTime> {
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%d/%m/%Y %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .ok_or_else(|| anyhow!("Invalid kick-off '{}'", value))
+}
+
+fn parse_date(value: &str) -> Result<NaiveDate> {
+    ["%Y-%m-%d", "%d/%m/%Y", "%d/%m/%y"]
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .ok_or_else(|| anyhow!("Invalid date '{}'", value))
+}
+
+/// Kick-off time of day, 3pm when none is given
+fn parse_time(value: Option<&str>) -> Result<NaiveTime> {
+    let Some(value) = value else {
+        return Ok(NaiveTime::from_hms_opt(15, 0, 0).unwrap());
+    };
+    ["%H:%M", "%H:%M:%S", "%H.%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(value, format).ok())
+        .ok_or_else(|| anyhow!("Invalid time '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spreadsheet_export() {
+        let content = "\
+Date,Time,Home,Away,Competition,Period Minutes,Venue
+18/10/2026,14:30,HRUFC,Henley Hawks,Counties 1,,Main Pitch
+25/10/2026,,Reading,HRUFC,,35,
+01/11/2026,15:00,HRUFC,,,,
+";
+        let fixtures = parse(content).unwrap();
+        assert_eq!(fixtures.len(), 2);
+
+        assert_eq!(fixtures[0].home, "HRUFC");
+        assert_eq!(fixtures[0].away, "Henley Hawks");
+        assert_eq!(fixtures[0].kick_off.to_string(), "2026-10-18 14:30:00");
+        assert_eq!(fixtures[0].competition.as_deref(), Some("Counties 1"));
+        assert_eq!(fixtures[0].venue.as_deref(), Some("Main Pitch"));
+
+        assert_eq!(fixtures[1].kick_off.to_string(), "2026-10-25 15:00:00");
+        assert_eq!(fixtures[1].period_minutes, Some(35));
+    }
+
+    #[test]
+    fn test_bad_rows_are_reported() {
+        let error = parse("home,away,kick_off\nA,B,next week\n").unwrap_err();
+        assert!(format!("{:#}", error).contains("Line 2"));
+        assert!(parse("home,away\nA,B\n").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, Utc};
+use anyhow::{Context, Result, anyhow};
+
+use crate::fixtures::Fixture;
+
+/// Parse fixtures from the events of an iCalendar file. Each event's
+/// summary names the teams as "Home v Away" (or "vs"); its first category
+/// is taken as the competition and its location as the venue. Events
+/// whose summary doesn't name two teams are skipped.
+pub fn parse(content: &str) -> Result<Vec<Fixture>> {
+    let mut fixtures = Vec::new();
+    let mut event: Option<Vec<(String, String)>> = None;
+
+    for line in unfold(content) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters such as TZID follow the property name after a ';'
+        let (property, params) = name.split_once(';').unwrap_or((name, ""));
+
+        match (property.to_ascii_uppercase().as_str(), value) {
+            ("BEGIN", "VEVENT") => event = Some(Vec::new()),
+            ("END", "VEVENT") => {
+                if let Some(properties) = event.take()
+                    && let Some(fixture) = fixture(&properties)?
+                {
+                    fixtures.push(fixture);
+                }
+            }
+            (property, value) => {
+                if let Some(properties) = event.as_mut() {
+                    properties.push((format!("{};{}", property, params), value.to_string()));
+                }
+            }
+        }
+    }
+    Ok(fixtures)
+}
+
+fn fixture(properties: &[(String, String)]) -> Result<Option<Fixture>> {
+    let property = |name: &str| {
+        properties.iter()
+            .find(|(key, _)| key.split(';').next() == Some(name))
+            .map(|(key, value)| (key.as_str(), unescape(value)))
+    };
+
+    let Some((home, away)) = property("SUMMARY").and_then(|(_, summary)| teams(&summary)) else {
+        return Ok(None);
+    };
+    let (key, start) = property("DTSTART").ok_or_else(|| anyhow!("Event '{} v {}' has no start", home, away))?;
+    let kick_off = parse_start(key, &start)
+        .with_context(|| format!("Event '{} v {}'", home, away))?;
+
+    let mut fixture = Fixture::new(home, away, kick_off);
+    fixture.competition = property("CATEGORIES")
+        .and_then(|(_, categories)| categories.split(',').next().map(|c| c.trim().to_string()))
+        .filter(|competition| !competition.is_empty());
+    fixture.venue = property("LOCATION").map(|(_, location)| location).filter(|venue| !venue.is_empty());
+    Ok(Some(fixture))
+}
+
+/// Split a summary like "HRUFC v Henley Hawks" into the two teams
+fn teams(summary: &str) -> Option<(String, String)> {
+    [" vs. ", " vs ", " v. ", " v ", " V "]
+        .iter()
+        .find_map(|separator| summary.split_once(separator))
+        .map(|(home, away)| (home.trim().to_string(), away.trim().to_string()))
+        .filter(|(home, away)| !home.is_empty() && !away.is_empty())
+}
+
+/// Start time in local time. UTC times (ending in Z) are converted, times
+/// with a TZID or no zone are taken as local, and all-day events kick off
+/// at 3pm.
+fn parse_start(key: &str, value: &str) -> Result<NaiveDateTime> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let utc = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")?;
+        let local: DateTime<Local> = DateTime::<Utc>::from_naive_utc_and_offset(utc, Utc).into();
+        return Ok(local.naive_local());
+    }
+    if key.contains("VALUE=DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")?;
+        return Ok(date.and_hms_opt(15, 0, 0).unwrap());
+    }
+    Ok(NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")?)
+}
+
+/// Join lines continued with leading whitespace (RFC 5545 folding)
+fn unfold(content: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+    lines
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_events() {
+        let content = "\
+BEGIN:VCALENDAR\r
+VERSION:2.0\r
+BEGIN:VEVENT\r
+SUMMARY:HRUFC v Henley Hawks\r
+DTSTART;TZID=Europe/London:20261018T143000\r
+CATEGORIES:Counties 1,League\r
+LOCATION:Main Pitch\\, Club\r
+  House\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Club AGM\r
+DTSTART:20261020T190000\r
+END:VEVENT\r
+BEGIN:VEVENT\r
+SUMMARY:Reading vs HRUFC\r
+DTSTART;VALUE=DATE:20261025\r
+END:VEVENT\r
+END:VCALENDAR\r
+";
+        let fixtures = parse(content).unwrap();
+        assert_eq!(fixtures.len(), 2);
+
+        assert_eq!(fixtures[0].home, "HRUFC");
+        assert_eq!(fixtures[0].away, "Henley Hawks");
+        assert_eq!(fixtures[0].kick_off.to_string(), "2026-10-18 14:30:00");
+        assert_eq!(fixtures[0].competition.as_deref(), Some("Counties 1"));
+        assert_eq!(fixtures[0].venue.as_deref(), Some("Main Pitch, Club House"));
+
+        assert_eq!(fixtures[1].home, "Reading");
+        assert_eq!(fixtures[1].kick_off.to_string(), "2026-10-25 15:00:00");
+    }
+}
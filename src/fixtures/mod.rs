@@ -0,0 +1,163 @@
+use std::path::Path;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, bail};
+
+use crate::store::YamlStore;
+use crate::teams::slug;
+
+pub mod csv;
+pub mod ical;
+
+/// File the fixtures are kept in, next to config.yaml
+pub const FIXTURES_FILE: &str = "fixtures.yaml";
+
+/// A scheduled match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fixture {
+    pub id: String,
+    pub home: String,
+    pub away: String,
+    /// Local kick-off time
+    pub kick_off: NaiveDateTime,
+    /// Competition whose rules apply, see `competitions` in config.yaml
+    #[serde(default)]
+    pub competition: Option<String>,
+    /// Length of each half when it differs from the competition's
+    #[serde(default)]
+    pub period_minutes: Option<u8>,
+    #[serde(default)]
+    pub venue: Option<String>,
+}
+
+impl Fixture {
+    pub fn new(home: String, away: String, kick_off: NaiveDateTime) -> Self {
+        let id = format!("{}-{}-v-{}", kick_off.format("%Y%m%d%H%M"), slug(&home), slug(&away));
+        Self {
+            id,
+            home,
+            away,
+            kick_off,
+            competition: None,
+            period_minutes: None,
+            venue: None,
+        }
+    }
+}
+
+/// Fixture file formats that can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    Csv,
+    Ics,
+}
+
+impl ImportFormat {
+    /// Guess the format of a file from its contents
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start().starts_with("BEGIN:VCALENDAR") {
+            ImportFormat::Ics
+        } else {
+            ImportFormat::Csv
+        }
+    }
+}
+
+/// Parse fixtures from a CSV or iCalendar file
+pub fn parse(content: &str, format: ImportFormat) -> Result<Vec<Fixture>> {
+    match format {
+        ImportFormat::Csv => csv::parse(content),
+        ImportFormat::Ics => ical::parse(content),
+    }
+}
+
+/// Fixtures persisted to a YAML file, saved after every change
+pub struct FixtureStore {
+    fixtures: YamlStore<Fixture>,
+}
+
+impl FixtureStore {
+    /// Load the fixtures from a file, see [`YamlStore::load`]
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        Self { fixtures: YamlStore::load(path) }
+    }
+
+    /// All fixtures in kick-off order
+    pub async fn list(&self) -> Vec<Fixture> {
+        self.fixtures.lock().await.clone()
+    }
+
+    /// Fixtures kicking off at or after the given time
+    pub async fn upcoming(&self, from: NaiveDateTime) -> Vec<Fixture> {
+        self.fixtures.lock().await
+            .iter()
+            .filter(|fixture| fixture.kick_off >= from)
+            .cloned()
+            .collect()
+    }
+
+    /// Look up a fixture by ID
+    pub async fn get(&self, id: &str) -> Option<Fixture> {
+        self.fixtures.lock().await.iter().find(|fixture| fixture.id == id).cloned()
+    }
+
+    /// Add fixtures, replacing any with the same ID so a file can be
+    /// imported again after it changes. Returns how many were imported.
+    pub async fn import(&self, imported: Vec<Fixture>) -> Result<usize> {
+        self.fixtures.update(|fixtures| {
+            let count = imported.len();
+            for fixture in imported {
+                match fixtures.iter_mut().find(|existing| existing.id == fixture.id) {
+                    Some(existing) => *existing = fixture,
+                    None => fixtures.push(fixture),
+                }
+            }
+            fixtures.sort_by_key(|fixture| fixture.kick_off);
+            Ok(count)
+        }).await
+    }
+
+    /// Remove a fixture
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        self.fixtures.update(|fixtures| {
+            let count = fixtures.len();
+            fixtures.retain(|fixture| fixture.id != id);
+            if fixtures.len() == count {
+                bail!("No fixture with ID '{}'", id);
+            }
+            Ok(())
+        }).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use chrono::NaiveDate;
+
+    fn kick_off(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(15, 0, 0).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_reimport_replaces_fixtures() {
+        let path = std::env::temp_dir().join(format!("fixtures-{}.yaml", std::process::id()));
+        let store = FixtureStore::load(&path);
+
+        let later = Fixture::new("HRUFC".into(), "Henley".into(), kick_off(24));
+        let sooner = Fixture::new("Reading".into(), "HRUFC".into(), kick_off(17));
+        store.import(vec![later.clone(), sooner.clone()]).await.unwrap();
+        assert_eq!(later.id, "202610241500-hrufc-v-henley");
+
+        let moved_venue = Fixture { venue: Some("Pitch 2".into()), ..later.clone() };
+        store.import(vec![moved_venue.clone()]).await.unwrap();
+
+        let reloaded = FixtureStore::load(&path);
+        assert_eq!(reloaded.list().await, vec![sooner, moved_venue.clone()]);
+        assert_eq!(reloaded.upcoming(kick_off(20)).await, vec![moved_venue]);
+
+        fs::remove_file(path).unwrap();
+    }
+}
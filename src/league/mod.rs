@@ -0,0 +1,238 @@
+use std::cmp::Reverse;
+use std::path::Path;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, bail};
+
+use crate::config::LeagueConfig;
+use crate::scoreboard::{ScoreEvent, Side};
+use crate::store::YamlStore;
+use crate::teams::slug;
+
+/// File the results are kept in, next to config.yaml
+pub const LEAGUE_FILE: &str = "league.yaml";
+
+/// Score actions that count towards a team's tries
+pub const TRY_ACTIONS: [&str; 2] = ["try", "penalty_try"];
+
+/// Tries a team scored, counted from the recorded scores
+pub fn tries(events: &[ScoreEvent], side: Side) -> u16 {
+    events.iter()
+        .filter(|event| event.team == side && TRY_ACTIONS.contains(&event.action.as_str()))
+        .count() as u16
+}
+
+/// The final score of a finished match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatchResult {
+    pub id: String,
+    #[serde(default)]
+    pub competition: Option<String>,
+    pub home: String,
+    pub away: String,
+    pub home_score: u16,
+    pub away_score: u16,
+    pub home_tries: u16,
+    pub away_tries: u16,
+    /// Kick-off, or for matches not set up from a fixture when the clock
+    /// was started
+    pub played_at: NaiveDateTime,
+}
+
+impl MatchResult {
+    /// ID in the same form as the fixture's, so recording a match again
+    /// replaces its result
+    pub fn id(home: &str, away: &str, played_at: NaiveDateTime) -> String {
+        format!("{}-{}-v-{}", played_at.format("%Y%m%d%H%M"), slug(home), slug(away))
+    }
+}
+
+/// A team's line in the league table
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Standing {
+    pub team: String,
+    pub played: u16,
+    pub won: u16,
+    pub drawn: u16,
+    pub lost: u16,
+    pub points_for: u32,
+    pub points_against: u32,
+    pub tries_for: u32,
+    pub tries_against: u32,
+    pub bonus_points: u16,
+    pub points: u16,
+}
+
+impl Standing {
+    pub fn points_difference(&self) -> i64 {
+        self.points_for as i64 - self.points_against as i64
+    }
+
+    fn add(&mut self, rules: &LeagueConfig, scored: u16, conceded: u16, tries: u16, tries_conceded: u16) {
+        self.played += 1;
+        self.points_for += scored as u32;
+        self.points_against += conceded as u32;
+        self.tries_for += tries as u32;
+        self.tries_against += tries_conceded as u32;
+
+        let points = match scored.cmp(&conceded) {
+            std::cmp::Ordering::Greater => {
+                self.won += 1;
+                rules.win_points
+            }
+            std::cmp::Ordering::Equal => {
+                self.drawn += 1;
+                rules.draw_points
+            }
+            std::cmp::Ordering::Less => {
+                self.lost += 1;
+                rules.loss_points
+            }
+        };
+
+        let mut bonus = 0;
+        let counted_tries = if rules.try_bonus_relative { tries.saturating_sub(tries_conceded) } else { tries };
+        if rules.try_bonus_tries.is_some_and(|needed| counted_tries >= needed) {
+            bonus += 1;
+        }
+        if scored < conceded && rules.losing_bonus_margin.is_some_and(|margin| conceded - scored <= margin) {
+            bonus += 1;
+        }
+        self.bonus_points += bonus;
+        self.points += points as u16 + bonus;
+    }
+}
+
+/// Results of finished matches persisted to a YAML file, from which the
+/// table is worked out under the configured points rules
+pub struct LeagueTable {
+    rules: LeagueConfig,
+    results: YamlStore<MatchResult>,
+}
+
+impl LeagueTable {
+    /// Load the results from a file, see [`YamlStore::load`]
+    pub fn load(path: impl AsRef<Path>, rules: LeagueConfig) -> Self {
+        Self {
+            rules,
+            results: YamlStore::load(path),
+        }
+    }
+
+    /// All results, most recent first
+    pub async fn results(&self) -> Vec<MatchResult> {
+        let mut results = self.results.lock().await.clone();
+        results.sort_by_key(|result| Reverse(result.played_at));
+        results
+    }
+
+    /// Record a result, replacing an earlier one for the same match
+    pub async fn record(&self, result: MatchResult) -> Result<()> {
+        self.results.update(|results| {
+            match results.iter_mut().find(|existing| existing.id == result.id) {
+                Some(existing) => *existing = result,
+                None => results.push(result),
+            }
+            Ok(())
+        }).await
+    }
+
+    /// Remove a result
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        self.results.update(|results| {
+            let count = results.len();
+            results.retain(|result| result.id != id);
+            if results.len() == count {
+                bail!("No result with ID '{}'", id);
+            }
+            Ok(())
+        }).await
+    }
+
+    /// The table of one competition, or of every result when none is
+    /// given, ordered by points, then points difference, then tries
+    pub async fn table(&self, competition: Option<&str>) -> Vec<Standing> {
+        let results = self.results.lock().await;
+        let mut table: Vec<Standing> = Vec::new();
+        let mut standing = |team: &str| -> usize {
+            match table.iter().position(|standing| standing.team == team) {
+                Some(index) => index,
+                None => {
+                    table.push(Standing { team: team.to_string(), ..Standing::default() });
+                    table.len() - 1
+                }
+            }
+        };
+
+        let mut lines = Vec::new();
+        for result in results.iter().filter(|result| competition.is_none() || result.competition.as_deref() == competition) {
+            lines.push((standing(&result.home), result.home_score, result.away_score, result.home_tries, result.away_tries));
+            lines.push((standing(&result.away), result.away_score, result.home_score, result.away_tries, result.home_tries));
+        }
+        for (index, scored, conceded, tries, tries_conceded) in lines {
+            table[index].add(&self.rules, scored, conceded, tries, tries_conceded);
+        }
+
+        table.sort_by(|a, b| {
+            b.points.cmp(&a.points)
+                .then(b.points_difference().cmp(&a.points_difference()))
+                .then(b.tries_for.cmp(&a.tries_for))
+                .then(a.team.cmp(&b.team))
+        });
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use chrono::NaiveDate;
+
+    fn result(home: &str, away: &str, scores: (u16, u16), tries: (u16, u16), day: u32) -> MatchResult {
+        let played_at = NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(15, 0, 0).unwrap();
+        MatchResult {
+            id: MatchResult::id(home, away, played_at),
+            competition: Some("Counties 1".to_string()),
+            home: home.to_string(),
+            away: away.to_string(),
+            home_score: scores.0,
+            away_score: scores.1,
+            home_tries: tries.0,
+            away_tries: tries.1,
+            played_at,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_table_with_bonus_points() {
+        let path = std::env::temp_dir().join(format!("league-{}.yaml", std::process::id()));
+        let league = LeagueTable::load(&path, LeagueConfig::default());
+
+        // Four-try win against a losing bonus point
+        league.record(result("HRUFC", "Henley Hawks", (28, 22), (4, 3), 3)).await.unwrap();
+        league.record(result("Reading", "HRUFC", (10, 10), (1, 1), 10)).await.unwrap();
+        // Recording a match again replaces its result
+        league.record(result("Henley Hawks", "Reading", (20, 3), (2, 0), 17)).await.unwrap();
+        league.record(result("Henley Hawks", "Reading", (30, 3), (5, 0), 17)).await.unwrap();
+
+        let table = league.table(Some("Counties 1")).await;
+        let teams: Vec<(&str, u16, u16)> = table.iter().map(|s| (s.team.as_str(), s.points, s.bonus_points)).collect();
+        assert_eq!(teams, vec![("HRUFC", 7, 1), ("Henley Hawks", 6, 2), ("Reading", 2, 0)]);
+        assert_eq!(table[1].points_difference(), 21);
+        assert!(league.table(Some("Cup")).await.is_empty());
+
+        let relative = LeagueConfig { try_bonus_tries: Some(3), try_bonus_relative: true, ..LeagueConfig::default() };
+        let reloaded = LeagueTable::load(&path, relative);
+        assert_eq!(reloaded.results().await.len(), 3);
+        // Only Henley's five tries to none are three more than the opponent's
+        let table = reloaded.table(None).await;
+        assert_eq!(table.iter().map(|s| s.bonus_points).sum::<u16>(), 2);
+
+        let latest = reloaded.results().await[0].id.clone();
+        assert_eq!(latest, "202610171500-henley-hawks-v-reading");
+        reloaded.delete(&latest).await.unwrap();
+        assert!(reloaded.delete(&latest).await.is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,11 @@
+pub mod archive;
+pub mod config;
+pub mod fixtures;
+pub mod league;
+pub mod mock;
+pub mod protocol;
+pub mod rules;
+pub mod scoreboard;
+pub mod store;
+pub mod teams;
+pub mod web;
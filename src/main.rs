@@ -1,14 +1,16 @@
-use std::sync::Arc;
 use std::env;
+use std::sync::Arc;
+use std::time::Duration;
 use log::info;
 
-mod config;
-mod protocol;
-mod scoreboard;
-mod web;
-
-use config::Config;
-use scoreboard::ScoreboardController;
+use scoreboard_server::config::Config;
+use scoreboard_server::protocol::discovery;
+use scoreboard_server::scoreboard::{Pitches, sponsors::SPONSORSHIP_DIR};
+use scoreboard_server::teams::{TeamRegistry, TEAMS_FILE};
+use scoreboard_server::fixtures::{FixtureStore, FIXTURES_FILE};
+use scoreboard_server::league::{LeagueTable, LEAGUE_FILE};
+use scoreboard_server::archive::{MatchArchive, MATCHES_DIR};
+use scoreboard_server::web;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -20,13 +22,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         Config::default()
     });
 
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("discover") {
+        return run_discover(config, &args[1..]).await;
+    }
+
     // Override config with environment variables if present
-    let scoreboard_address = env::var("SCOREBOARD_ADDRESS")
-        .unwrap_or_else(|_| config.scoreboard.address.clone());
-    let card_id = env::var("CARD_ID")
-        .unwrap_or_else(|_| config.scoreboard.card_id.to_string())
-        .parse::<u8>()
-        .unwrap_or(config.scoreboard.card_id);
+    let mut config = config;
+    let address = env::var("SCOREBOARD_ADDRESS").ok();
+    let card_id = env::var("CARD_ID").ok().and_then(|card_id| card_id.parse::<u8>().ok());
+    if let Some(address) = &address {
+        config.scoreboard.address = address.clone();
+    }
+    if let Some(card_id) = card_id {
+        config.scoreboard.card_id = card_id;
+    }
+    // With `boards` or `pitches` the `scoreboard` section isn't a board, so
+    // the overrides go to the primary board as well
+    if (address.is_some() || card_id.is_some()) && (!config.boards.is_empty() || !config.pitches.is_empty()) {
+        let primary = config.pitches().into_iter().next().and_then(|pitch| pitch.boards.into_iter().next());
+        if let Some(primary) = primary {
+            let target = config.set_board_target(None, None, address.unwrap_or(primary.address), card_id.unwrap_or(primary.card_id));
+            if let Err(e) = target {
+                log::warn!("Ignoring SCOREBOARD_ADDRESS and CARD_ID: {}", e);
+            }
+        }
+    }
     let web_port = env::var("WEB_PORT")
         .unwrap_or_else(|_| config.server.web_port.to_string())
         .parse::<u16>()
@@ -39,56 +60,78 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Starting HRUFC Rugby Scoreboard Server");
     info!("Configuration loaded from config.yaml");
     info!("Simulation mode: {}", simulation_mode);
-    info!("Scoreboard address: {}", scoreboard_address);
-    info!("Card ID: {}", card_id);
+    for pitch in config.pitches() {
+        for board in &pitch.boards {
+            info!("Pitch '{}' board '{}': address {}, card ID {}", pitch.id, board.name, board.address, board.card_id);
+        }
+    }
     
     // Display web server access information
     let access_url = if web_port == 80 {
-        format!("http://localhost/")
+        "http://localhost/".to_string()
     } else {
         format!("http://localhost:{}/", web_port)
     };
     info!("Web server will start on port {}", web_port);
     info!("Access the scoreboard interface at: {}", access_url);
 
-    // Create scoreboard controller
-    let controller = Arc::new(ScoreboardController::new(
-        scoreboard_address,
-        card_id,
-        simulation_mode,
-        config.clone(),
-    ));
-
-    // Initialize the scoreboard (connect and set up display) - skip if simulation mode
-    if !simulation_mode {
-        info!("Initializing scoreboard connection...");
-        match controller.initialize().await {
-            Ok(_) => info!("Scoreboard initialized successfully"),
-            Err(e) => {
-                log::error!("Failed to initialize scoreboard: {}. Will continue with web server.", e);
-                // Continue running even if we can't connect to scoreboard initially
-                // This allows the web interface to be accessible for configuration
-            }
-        }
+    // Create a scoreboard controller for every pitch
+    let pitches = Pitches::new(simulation_mode, &config, SPONSORSHIP_DIR);
 
-        // Start connection monitoring in background
-        let controller_monitor = controller.clone();
-        let reconnect_interval = config.scoreboard.reconnect_interval_seconds;
+    // Run the match clock of every pitch. The card's own clock can't be read
+    // back, so the server keeps the time that scores, sin bins, the shot
+    // clock, breaks and messages are timed against, and prioritises it in
+    // the writer like any other window.
+    for pitch in pitches.iter() {
+        let controller_clock = pitch.controller.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(reconnect_interval));
+            let mut interval = tokio::time::interval(Duration::from_secs(1));
             loop {
                 interval.tick().await;
-                if let Err(e) = controller_monitor.ensure_connection().await {
-                    log::warn!("Connection check failed: {}", e);
-                }
+                controller_clock.tick().await;
             }
         });
+    }
+
+    // Initialize the scoreboards (connect and set up display) - skip if simulation mode
+    if !simulation_mode {
+        for pitch in pitches.iter() {
+            info!("Initializing scoreboard connection for pitch '{}'...", pitch.id);
+            match pitch.controller.initialize().await {
+                Ok(_) => info!("Pitch '{}' initialized successfully", pitch.id),
+                Err(e) => {
+                    log::error!("Failed to initialize pitch '{}': {}. Will continue with web server.", pitch.id, e);
+                    // Continue running even if we can't connect to scoreboard initially
+                    // This allows the web interface to be accessible for configuration
+                }
+            }
+
+            // Start connection monitoring in background
+            let controller_monitor = pitch.controller.clone();
+            let pitch_id = pitch.id.clone();
+            let reconnect_interval = config.scoreboard.reconnect_interval_seconds;
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(reconnect_interval));
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = controller_monitor.ensure_connection().await {
+                        log::warn!("Connection check failed for pitch '{}': {}", pitch_id, e);
+                    }
+                }
+            });
+        }
     } else {
         info!("Running in simulation mode - no physical scoreboard connection");
     }
 
+    // Load the team registry and fixtures
+    let teams = Arc::new(TeamRegistry::load(TEAMS_FILE));
+    let fixtures = Arc::new(FixtureStore::load(FIXTURES_FILE));
+    let league = Arc::new(LeagueTable::load(LEAGUE_FILE, config.league.clone()));
+    let archive = Arc::new(MatchArchive::load(MATCHES_DIR)?);
+
     // Create web routes
-    let routes = web::create_routes(controller);
+    let routes = web::create_routes(pitches, teams, fixtures, league, archive);
 
     // Start web server
     info!("Web server started - Access at: {}", access_url);
@@ -98,3 +141,57 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// `scoreboard-server discover [--port PORT] [--timeout SECONDS] [--use N] [--pitch ID] [--board NAME]`
+///
+/// Lists the control cards found on the network. With `--use N` the N-th
+/// card (1-based) is written to config.yaml, for the board named by
+/// `--pitch`/`--board` or the first board of the first pitch.
+async fn run_discover(mut config: Config, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut port = discovery::port_from_address(&config.scoreboard.address);
+    let mut wait = Duration::from_secs(3);
+    let mut selected: Option<usize> = None;
+    let mut pitch: Option<String> = None;
+    let mut board: Option<String> = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+        match arg.as_str() {
+            "--port" => port = value.parse()?,
+            "--timeout" => wait = Duration::from_secs(value.parse()?),
+            "--use" => selected = Some(value.parse()?),
+            "--pitch" => pitch = Some(value.clone()),
+            "--board" => board = Some(value.clone()),
+            _ => return Err(format!("Unknown option: {}", arg).into()),
+        }
+    }
+
+    println!("Searching for control cards on UDP port {}...", port);
+    let cards = discovery::discover(port, wait).await?;
+    if cards.is_empty() {
+        println!("No control cards found");
+        return Ok(());
+    }
+
+    for (index, card) in cards.iter().enumerate() {
+        println!(
+            "{:>2}. {:<21} card ID {:<3} firmware {}",
+            index + 1,
+            card.address,
+            card.card_id,
+            card.firmware.as_deref().unwrap_or("unknown")
+        );
+    }
+
+    if let Some(number) = selected {
+        let card = number.checked_sub(1)
+            .and_then(|index| cards.get(index))
+            .ok_or_else(|| format!("No card number {}", number))?;
+        let name = config.set_board_target(pitch.as_deref(), board.as_deref(), card.address.clone(), card.card_id)?;
+        config.save()?;
+        println!("Saved {} (card ID {}) to config.yaml for board '{}'", card.address, card.card_id, name);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,303 @@
+use log::{debug, info, warn};
+
+use crate::protocol::{Color, Picture, SIMPLE_PICTURE_FORMAT, VersionInfo, WindowImage};
+
+/// A window as held by the control card
+#[derive(Debug, Clone, PartialEq)]
+pub struct VirtualWindow {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+    pub text: String,
+    pub color: Color,
+    pub mode: u8,
+    pub alignment: u8,
+    pub speed: u8,
+    pub display_time: u16,
+    pub font: u8,
+    /// Picture shown in place of text
+    pub image: Option<WindowImage>,
+}
+
+impl VirtualWindow {
+    fn new(x: u16, y: u16, width: u16, height: u16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            text: String::new(),
+            color: Color::BLACK,
+            mode: 0,
+            alignment: 0,
+            speed: 0,
+            display_time: 0,
+            font: 0,
+            image: None,
+        }
+    }
+}
+
+/// 0x7b data split over several packets, collected until the last one arrives
+#[derive(Debug)]
+struct PartialMessage {
+    subcommand: u8,
+    next_packet: u8,
+    data: Vec<u8>,
+}
+
+/// Everything the control card would show or remember
+#[derive(Debug)]
+pub struct VirtualDisplay {
+    pub windows: Vec<VirtualWindow>,
+    pub brightness: [u8; 24],
+    pub power_on: bool,
+    /// Seconds, minutes, hours, day of week, date, month, 2-digit year
+    pub clock: [u8; 7],
+    pub version: VersionInfo,
+    /// Packets the card could not make sense of
+    pub errors: Vec<String>,
+    partial: Option<PartialMessage>,
+}
+
+impl Default for VirtualDisplay {
+    fn default() -> Self {
+        Self {
+            windows: Vec::new(),
+            brightness: [0x1f; 24],
+            power_on: true,
+            clock: [0, 0, 0, 0, 1, 1, 0],
+            version: VersionInfo { boot: 0x0100, nxp: 0x0100, bios: 0x0100, app: 0x0305, net: 0x0100, logic: 0x0100 },
+            errors: Vec::new(),
+            partial: None,
+        }
+    }
+}
+
+/// Acknowledge values of 0x7b responses
+const ACK_SUCCESS: u8 = 0x00;
+const ACK_CHECKSUM_ERROR: u8 = 0x01;
+const ACK_SEQUENCE_ERROR: u8 = 0x02;
+
+impl VirtualDisplay {
+    /// Text shown in a window, if it exists
+    pub fn text(&self, window_id: u8) -> Option<&str> {
+        self.windows.get(window_id as usize).map(|window| window.text.as_str())
+    }
+
+    /// Apply the command data of a packet, returning the command data of the
+    /// reply if one was requested
+    pub fn handle(&mut self, data: &[u8]) -> Option<Vec<u8>> {
+        let (&command, rest) = data.split_first()?;
+        let response_requested = rest.first() == Some(&0x01);
+        let body = rest.get(1..).unwrap_or_default();
+
+        let response = match command {
+            0x2d => {
+                info!("Restarting");
+                self.windows.clear();
+                self.partial = None;
+                vec![0x2d, 0x01]
+            }
+            0x46 => self.brightness_control(body),
+            0x47 => self.time_control(body),
+            0x4b => self.version.encode(),
+            0x76 => self.power_control(body),
+            0x7b => vec![0x7b, self.display_message(body)],
+            _ => {
+                self.error(format!("Unknown command 0x{:02x}", command));
+                vec![command, 0x01]
+            }
+        };
+
+        response_requested.then_some(response)
+    }
+
+    /// Reply to a 0x7b packet that failed its checksum
+    pub fn checksum_error(&mut self) -> Vec<u8> {
+        self.error("Packet checksum mismatch".to_string());
+        vec![0x7b, ACK_CHECKSUM_ERROR]
+    }
+
+    fn brightness_control(&mut self, body: &[u8]) -> Vec<u8> {
+        match body.split_first() {
+            Some((0x01, _)) => {
+                let mut response = vec![0x46, 0x01, 0x01];
+                response.extend_from_slice(&self.brightness);
+                response
+            }
+            Some((0x00, values)) if values.len() >= 24 => {
+                self.brightness.copy_from_slice(&values[..24]);
+                vec![0x46, 0x01]
+            }
+            _ => {
+                self.error(format!("Invalid brightness control {:02x?}", body));
+                vec![0x46, 0x01]
+            }
+        }
+    }
+
+    fn time_control(&mut self, body: &[u8]) -> Vec<u8> {
+        match body.split_first() {
+            Some((0x01, _)) => {
+                let mut response = vec![0x47, 0x01, 0x01];
+                response.extend_from_slice(&self.clock);
+                response
+            }
+            Some((0x00, values)) if values.len() >= 7 => {
+                self.clock.copy_from_slice(&values[..7]);
+                vec![0x47, 0x01]
+            }
+            _ => {
+                self.error(format!("Invalid time control {:02x?}", body));
+                vec![0x47, 0x01]
+            }
+        }
+    }
+
+    fn power_control(&mut self, body: &[u8]) -> Vec<u8> {
+        match body.split_first() {
+            Some((0x01, _)) => {
+                let mut response = vec![0x76, 0x01, 0x01, self.power_on as u8];
+                response.extend_from_slice(&[0u8; 8]);
+                response
+            }
+            Some((0x00, values)) if !values.is_empty() => {
+                self.power_on = values[0] == 0x01;
+                info!("Display turned {}", if self.power_on { "on" } else { "off" });
+                vec![0x76, 0x01]
+            }
+            _ => {
+                self.error(format!("Invalid power control {:02x?}", body));
+                vec![0x76, 0x01]
+            }
+        }
+    }
+
+    /// Handle the packet protocol of the 0x7b command, returning the acknowledge value
+    fn display_message(&mut self, body: &[u8]) -> u8 {
+        if body.len() < 5 {
+            self.error(format!("Display message too short: {:02x?}", body));
+            return ACK_CHECKSUM_ERROR;
+        }
+
+        let data_length = u16::from_le_bytes([body[0], body[1]]) as usize;
+        let packet = body[2];
+        let last_packet = body[3];
+        if data_length != body.len() - 4 {
+            self.error(format!("Packet {} data length {} but {} bytes sent", packet, data_length, body.len() - 4));
+            return ACK_CHECKSUM_ERROR;
+        }
+
+        let expected = self.partial.as_ref().map_or(0, |partial| partial.next_packet);
+        if packet != expected {
+            self.error(format!("Expected packet {} but got {}", expected, packet));
+            self.partial = None;
+            return ACK_SEQUENCE_ERROR;
+        }
+
+        // Packets after the first carry only more data for its subcommand
+        let mut message = self.partial.take().unwrap_or(PartialMessage {
+            subcommand: body[4],
+            next_packet: 0,
+            data: Vec::new(),
+        });
+        message.data.extend_from_slice(if packet == 0 { &body[5..] } else { &body[4..] });
+        if packet < last_packet {
+            message.next_packet = packet + 1;
+            self.partial = Some(message);
+            return ACK_SUCCESS;
+        }
+
+        match message.subcommand {
+            0x01 => self.create_windows(&message.data),
+            0x03 => self.send_image(&message.data),
+            0x12 => self.send_pure_text(&message.data),
+            other => {
+                debug!("Ignoring unsupported subcommand 0x{:02x}", other);
+                self.error(format!("Unsupported subcommand 0x{:02x}", other));
+            }
+        }
+        ACK_SUCCESS
+    }
+
+    fn create_windows(&mut self, data: &[u8]) {
+        let count = data.first().copied().unwrap_or(0) as usize;
+        if !(1..=8).contains(&count) || data.len() != 1 + count * 8 {
+            self.error(format!("Invalid window list {:02x?}", data));
+            return;
+        }
+
+        // Coordinates are sent high byte first
+        let value = |offset: usize| u16::from_be_bytes([data[offset], data[offset + 1]]);
+        self.windows = (0..count)
+            .map(|index| {
+                let offset = 1 + index * 8;
+                VirtualWindow::new(value(offset), value(offset + 2), value(offset + 4), value(offset + 6))
+            })
+            .collect();
+        info!("Created {} window(s)", count);
+    }
+
+    fn send_pure_text(&mut self, data: &[u8]) {
+        if data.len() < 11 || data.last() != Some(&0x00) {
+            self.error(format!("Invalid pure text {:02x?}", data));
+            return;
+        }
+
+        let window_id = data[0];
+        let Some(window) = self.windows.get_mut(window_id as usize) else {
+            self.error(format!("Text sent to missing window {}", window_id));
+            return;
+        };
+
+        window.mode = data[1];
+        window.alignment = data[2];
+        window.speed = data[3];
+        window.display_time = u16::from_be_bytes([data[4], data[5]]);
+        window.font = data[6];
+        window.color = Color { red: data[7], green: data[8], blue: data[9] };
+        window.text = String::from_utf8_lossy(&data[10..data.len() - 1]).into_owned();
+        window.image = None;
+        info!("Window {} shows '{}'", window_id, window.text);
+    }
+
+    fn send_image(&mut self, data: &[u8]) {
+        if data.len() < 10 || data[5] != SIMPLE_PICTURE_FORMAT {
+            self.error(format!("Invalid image header {:02x?}", &data[..data.len().min(10)]));
+            return;
+        }
+        let picture = match Picture::decode(&data[10..]) {
+            Ok(picture) => picture,
+            Err(e) => {
+                self.error(format!("Invalid picture: {}", e));
+                return;
+            }
+        };
+
+        let window_id = data[0];
+        let Some(window) = self.windows.get_mut(window_id as usize) else {
+            self.error(format!("Image sent to missing window {}", window_id));
+            return;
+        };
+        let (x, y) = (u16::from_be_bytes([data[6], data[7]]), u16::from_be_bytes([data[8], data[9]]));
+        if x + picture.width > window.width || y + picture.height > window.height {
+            let message = format!("{}x{} image at {},{} overflows window {}", picture.width, picture.height, x, y, window_id);
+            self.error(message);
+            return;
+        }
+
+        window.mode = data[1];
+        window.speed = data[2];
+        window.display_time = u16::from_be_bytes([data[3], data[4]]);
+        window.text.clear();
+        info!("Window {} shows a {}x{} image", window_id, picture.width, picture.height);
+        window.image = Some(WindowImage { x, y, picture });
+    }
+
+    fn error(&mut self, message: String) {
+        warn!("{}", message);
+        self.errors.push(message);
+    }
+}
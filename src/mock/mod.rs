@@ -0,0 +1,247 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant, sleep};
+use anyhow::{Result, Context};
+use log::{debug, info, warn};
+
+use crate::protocol::{EthernetPacket, WindowImage};
+
+pub mod display;
+
+pub use display::{VirtualDisplay, VirtualWindow};
+
+/// Largest packet the card accepts: 512 bytes of 0x7b data plus headers
+const MAX_PACKET_LENGTH: usize = 1024;
+
+/// Stand-in for a CPower control card that listens on TCP like the real one
+/// and keeps a virtual model of what the display shows
+#[derive(Clone)]
+pub struct MockCard {
+    card_id: u8,
+    display: Arc<Mutex<VirtualDisplay>>,
+}
+
+impl MockCard {
+    /// Create a card with the given ID
+    pub fn new(card_id: u8) -> Self {
+        Self {
+            card_id,
+            display: Arc::new(Mutex::new(VirtualDisplay::default())),
+        }
+    }
+
+    /// ID of the card
+    pub fn card_id(&self) -> u8 {
+        self.card_id
+    }
+
+    /// What the card currently holds
+    pub fn display(&self) -> Arc<Mutex<VirtualDisplay>> {
+        self.display.clone()
+    }
+
+    /// Text shown in a window, if it exists
+    pub async fn text(&self, window_id: u8) -> Option<String> {
+        self.display.lock().await.text(window_id).map(str::to_string)
+    }
+
+    /// Image shown in a window, if any
+    pub async fn image(&self, window_id: u8) -> Option<WindowImage> {
+        self.display.lock().await.windows.get(window_id as usize).and_then(|window| window.image.clone())
+    }
+
+    /// Wait until a window shows the given text. Returns false on timeout.
+    pub async fn wait_for_text(&self, window_id: u8, text: &str, wait: Duration) -> bool {
+        let deadline = Instant::now() + wait;
+        loop {
+            if self.text(window_id).await.as_deref() == Some(text) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    /// Listen on the address and serve connections in the background,
+    /// returning the bound address
+    pub async fn start(&self, address: &str) -> Result<SocketAddr> {
+        let listener = TcpListener::bind(address).await
+            .with_context(|| format!("Failed to listen on {}", address))?;
+        let local_address = listener.local_addr()?;
+
+        let card = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = card.serve(listener).await {
+                warn!("Mock card stopped: {}", e);
+            }
+        });
+        Ok(local_address)
+    }
+
+    /// Serve connections until the listener fails
+    pub async fn serve(&self, listener: TcpListener) -> Result<()> {
+        info!("Mock card {} listening on {}", self.card_id, listener.local_addr()?);
+        loop {
+            let (socket, peer) = listener.accept().await?;
+            info!("Connection from {}", peer);
+            let card = self.clone();
+            tokio::spawn(async move {
+                if let Err(e) = card.handle_connection(socket).await {
+                    warn!("Connection from {} failed: {}", peer, e);
+                }
+                info!("Connection from {} closed", peer);
+            });
+        }
+    }
+
+    async fn handle_connection(&self, mut socket: TcpStream) -> Result<()> {
+        let mut buffer = Vec::new();
+        let mut chunk = vec![0u8; MAX_PACKET_LENGTH];
+        loop {
+            let n = socket.read(&mut chunk).await?;
+            if n == 0 {
+                return Ok(());
+            }
+            buffer.extend_from_slice(&chunk[..n]);
+
+            while let Some(length) = EthernetPacket::frame_length(&buffer) {
+                if !(13..=MAX_PACKET_LENGTH).contains(&length) {
+                    warn!("Discarding {} bytes with invalid packet length {}", buffer.len(), length);
+                    buffer.clear();
+                    break;
+                }
+                if buffer.len() < length {
+                    break;
+                }
+
+                let frame: Vec<u8> = buffer.drain(..length).collect();
+                if let Some(reply) = self.handle_packet(&frame).await {
+                    socket.write_all(&reply.encode()).await?;
+                }
+            }
+        }
+    }
+
+    /// Apply a received packet, returning the reply the card would send
+    pub async fn handle_packet(&self, frame: &[u8]) -> Option<EthernetPacket> {
+        debug!("Received {} bytes: {:02x?}", frame.len(), frame);
+        let mut display = self.display.lock().await;
+
+        let packet = match EthernetPacket::decode(frame) {
+            Ok(packet) => packet,
+            Err(e) => {
+                warn!("Failed to decode packet: {}", e);
+                // Only the display message command reports checksum errors
+                return (frame.get(11) == Some(&0x7b))
+                    .then(|| EthernetPacket::response(self.card_id, display.checksum_error()));
+            }
+        };
+
+        if packet.card_id != self.card_id && packet.card_id != 0xff {
+            debug!("Ignoring packet for card {}", packet.card_id);
+            return None;
+        }
+
+        display.handle(&packet.command_data)
+            .map(|data| EthernetPacket::response(self.card_id, data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{Command, DisplayCommand, Color, Picture, ScoreboardLayout, DEFAULT_FONT, effects, windows};
+
+    fn packet(card_id: u8, command: Command) -> Vec<u8> {
+        EthernetPacket::new(card_id, command.encode()).encode().to_vec()
+    }
+
+    #[tokio::test]
+    async fn test_windows_and_text() {
+        let card = MockCard::new(1);
+        let layout = ScoreboardLayout::standard_224x32();
+        let create = packet(1, Command::DisplayMessage(DisplayCommand::CreateWindows(layout.all_windows())));
+        let reply = card.handle_packet(&create).await.unwrap();
+        assert_eq!(reply.packet_type, 0xe8);
+        assert_eq!(reply.command_data, vec![0x7b, 0x00]);
+
+        let text = packet(1, Command::DisplayMessage(DisplayCommand::SendPureText {
+            window_id: windows::HOME_SCORE,
+            text: "12".to_string(),
+            color: Color::GREEN,
+            font: DEFAULT_FONT,
+            effect: effects::INSTANT,
+            speed: 1,
+        }));
+        card.handle_packet(&text).await.unwrap();
+
+        let display = card.display();
+        let display = display.lock().await;
+        assert!(display.errors.is_empty(), "{:?}", display.errors);
+        assert_eq!(display.windows.len(), 5);
+        assert_eq!(display.windows[4].x, 128);
+        assert_eq!(display.windows[4].width, 96);
+        assert_eq!(display.text(windows::HOME_SCORE), Some("12"));
+        assert_eq!(display.windows[windows::HOME_SCORE as usize].color, Color::GREEN);
+    }
+
+    #[tokio::test]
+    async fn test_images_are_reassembled() {
+        let card = MockCard::new(1);
+        let layout = ScoreboardLayout::standard_224x32();
+        card.handle_packet(&packet(1, Command::DisplayMessage(DisplayCommand::CreateWindows(vec![layout.fullscreen()])))).await.unwrap();
+
+        let picture = Picture {
+            width: 200,
+            height: 30,
+            pixels: (0..200 * 30).map(|index| if index % 7 == 0 { Color::WHITE } else { Color::BLUE }).collect(),
+        };
+        let image = Command::DisplayMessage(DisplayCommand::SendImage {
+            window_id: 0,
+            mode: effects::INSTANT,
+            speed: 1,
+            image: WindowImage { x: 12, y: 1, picture: picture.clone() },
+        });
+        let packets = image.encode_packets();
+        assert!(packets.len() > 1);
+        for data in &packets {
+            let reply = card.handle_packet(&EthernetPacket::new(1, data.clone()).encode()).await.unwrap();
+            assert_eq!(reply.command_data, vec![0x7b, 0x00]);
+        }
+
+        let display = card.display();
+        let display = display.lock().await;
+        assert!(display.errors.is_empty(), "{:?}", display.errors);
+        assert_eq!(display.windows[0].image, Some(WindowImage { x: 12, y: 1, picture }));
+
+        // Packets out of turn are refused
+        drop(display);
+        let reply = card.handle_packet(&EthernetPacket::new(1, packets[1].clone()).encode()).await.unwrap();
+        assert_eq!(reply.command_data, vec![0x7b, 0x02]);
+    }
+
+    #[tokio::test]
+    async fn test_bad_checksum_is_reported() {
+        let card = MockCard::new(1);
+        let mut create = packet(1, Command::DisplayMessage(DisplayCommand::CreateWindows(
+            ScoreboardLayout::standard_224x32().all_windows()
+        )));
+        let last = create.len() - 1;
+        create[last] ^= 0xff;
+
+        let reply = card.handle_packet(&create).await.unwrap();
+        assert_eq!(reply.command_data, vec![0x7b, 0x01]);
+    }
+
+    #[tokio::test]
+    async fn test_packets_for_other_cards_are_ignored() {
+        let card = MockCard::new(1);
+        assert!(card.handle_packet(&packet(2, Command::QueryVersion)).await.is_none());
+        assert!(card.handle_packet(&packet(0xff, Command::QueryVersion)).await.is_some());
+    }
+}
@@ -51,13 +51,39 @@ impl ScoreboardClient {
         self.stream.is_some()
     }
 
-    /// Send a command to the scoreboard
+    /// Address of the control card
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// ID of the control card
+    pub fn card_id(&self) -> u8 {
+        self.card_id
+    }
+
+    /// Send a command to the scoreboard, waiting for the card's response to
+    /// each packet of a command split over several
     pub async fn send_command(&mut self, command: Command) -> Result<Option<Vec<u8>>> {
+        let mut packets = command.encode_packets();
+        let last = packets.pop().unwrap_or_default();
+        for (index, data) in packets.into_iter().enumerate() {
+            if let Some(response) = self.send_packet(data).await?
+                && response.first() == Some(&0x7b)
+                && response.get(1).is_some_and(|ack| *ack != 0x00)
+            {
+                anyhow::bail!("Card rejected packet {} with 0x{:02x}", index, response[1]);
+            }
+        }
+        self.send_packet(last).await
+    }
+
+    /// Send the command data of one packet and read the response
+    async fn send_packet(&mut self, command_data: Vec<u8>) -> Result<Option<Vec<u8>>> {
         if self.stream.is_none() {
             self.connect().await?;
         }
 
-        let packet = EthernetPacket::new(self.card_id, command.encode());
+        let packet = EthernetPacket::new(self.card_id, command_data);
         let data = packet.encode();
         
         debug!("Sending packet: {:?}", packet);
@@ -65,8 +91,10 @@ impl ScoreboardClient {
 
         let stream = self.stream.as_mut().unwrap();
         
-        stream.write_all(&data).await
-            .context("Failed to send command")?;
+        if let Err(e) = stream.write_all(&data).await {
+            self.stream = None; // Mark as disconnected
+            return Err(e).context("Failed to send command");
+        }
 
         // Try to read response with timeout
         match timeout(Duration::from_secs(5), self.read_response()).await {
@@ -137,7 +165,6 @@ impl ScoreboardClient {
 /// Connection manager to handle automatic reconnection
 pub struct ConnectionManager {
     client: ScoreboardClient,
-    reconnect_interval: Duration,
     keep_alive_interval: Duration,
 }
 
@@ -145,7 +172,6 @@ impl ConnectionManager {
     pub fn new(address: String, card_id: u8) -> Self {
         Self {
             client: ScoreboardClient::new(address, card_id),
-            reconnect_interval: Duration::from_secs(10),
             keep_alive_interval: Duration::from_secs(30),
         }
     }
@@ -205,4 +231,16 @@ mod tests {
         assert_eq!(client.address, "127.0.0.1:5200");
         assert_eq!(client.card_id, 0x01);
     }
+
+    #[tokio::test]
+    async fn test_send_command_reads_response() {
+        let card = crate::mock::MockCard::new(3);
+        let address = card.start("127.0.0.1:0").await.unwrap();
+
+        let mut client = ScoreboardClient::new(address.to_string(), 3);
+        let response = client.send_command(Command::QueryVersion).await.unwrap().unwrap();
+        let version = crate::protocol::VersionInfo::parse(&response).unwrap();
+        assert_eq!(version.app_version(), "3.5");
+        assert!(client.is_connected());
+    }
 }
\ No newline at end of file
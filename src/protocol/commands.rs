@@ -1,4 +1,4 @@
-use bytes::{BufMut, BytesMut};
+use crate::protocol::{IMAGE_CHUNK_SIZE, SIMPLE_PICTURE_FORMAT, WindowImage};
 
 /// Commands for the CPower control card
 #[derive(Debug, Clone)]
@@ -33,13 +33,16 @@ pub enum DisplayCommand {
     CreateWindows(Vec<WindowData>),
     /// Send text to a window
     SendText { window_id: u8, text: String, color: Color },
-    /// Send pure text to window (simplified)
-    SendPureText { window_id: u8, text: String, color: Color },
+    /// Send pure text to window with a font, display effect and effect speed
+    SendPureText { window_id: u8, text: String, color: Color, font: u8, effect: u8, speed: u8 },
     /// Display time in window
     DisplayTime { window_id: u8 },
+    /// Show a picture in a window with a display mode and transition speed,
+    /// sent over as many packets as it needs
+    SendImage { window_id: u8, mode: u8, speed: u8, image: WindowImage },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct WindowData {
     pub x: u16,
     pub y: u16, 
@@ -47,19 +50,67 @@ pub struct WindowData {
     pub height: u16,
 }
 
-#[derive(Debug, Clone)]
+/// An RGB colour, written as `#rrggbb` in config files and the API
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct Color {
     pub red: u8,
     pub green: u8,
     pub blue: u8,
 }
 
+/// Font byte used for window text: default style, 16 point
+pub const DEFAULT_FONT: u8 = 0x02;
+
+/// Alignment byte used for window text: left aligned, vertically centred
+pub const DEFAULT_ALIGNMENT: u8 = 0x04;
+
+/// Height in points of a font size (bits 2 to 0 of the font byte)
+pub fn font_points(font: u8) -> u16 {
+    match font & 0x07 {
+        0 => 8,
+        1 => 12,
+        size => size as u16 * 8,
+    }
+}
+
 impl Color {
     pub const RED: Color = Color { red: 255, green: 0, blue: 0 };
     pub const GREEN: Color = Color { red: 0, green: 255, blue: 0 };
     pub const BLUE: Color = Color { red: 0, green: 0, blue: 255 };
     pub const WHITE: Color = Color { red: 255, green: 255, blue: 255 };
+    pub const YELLOW: Color = Color { red: 255, green: 255, blue: 0 };
     pub const BLACK: Color = Color { red: 0, green: 0, blue: 0 };
+
+    /// Parse `#rrggbb` (the `#` is optional)
+    pub fn from_hex(hex: &str) -> anyhow::Result<Self> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if digits.len() != 6 || !digits.is_ascii() {
+            anyhow::bail!("Invalid colour '{}', expected #rrggbb", hex);
+        }
+        let channel = |range| u8::from_str_radix(&digits[range], 16)
+            .map_err(|_| anyhow::anyhow!("Invalid colour '{}', expected #rrggbb", hex));
+        Ok(Color { red: channel(0..2)?, green: channel(2..4)?, blue: channel(4..6)? })
+    }
+
+    /// Format as `#rrggbb`
+    pub fn to_hex(&self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue)
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = anyhow::Error;
+
+    fn try_from(hex: String) -> anyhow::Result<Self> {
+        Color::from_hex(&hex)
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        color.to_hex()
+    }
 }
 
 impl Command {
@@ -82,7 +133,7 @@ impl Command {
                 }
             },
             Command::QueryVersion => {
-                vec![0x4b, 0x01]
+                vec![0x4b, 0x01, 0x00]
             },
             Command::PowerControl { query, power_on } => {
                 if *query {
@@ -96,38 +147,40 @@ impl Command {
                     DisplayCommand::CreateWindows(windows) => {
                         let mut data = vec![0x7b, 0x01]; // Command + Response request
                         
-                        // Data length (will be calculated)
-                        let data_length = 3 + (windows.len() * 8); // subcommand + packet info + window count + window data
+                        // Data length: subcommand + window count + window data
+                        let data_length = 2 + (windows.len() * 8);
                         data.extend_from_slice(&(data_length as u16).to_le_bytes());
                         
                         data.extend_from_slice(&[0x00, 0x00]); // Packet ID, Max Packet ID
                         data.push(0x01); // Subcommand: Create Windows
                         data.push(windows.len() as u8); // Number of windows
                         
+                        // Window coordinates are sent high byte first, as in the
+                        // protocol document's example of splitting a 96 x 32 display
                         for window in windows {
-                            data.extend_from_slice(&window.x.to_le_bytes());
-                            data.extend_from_slice(&window.y.to_le_bytes());
-                            data.extend_from_slice(&window.width.to_le_bytes());
-                            data.extend_from_slice(&window.height.to_le_bytes());
+                            data.extend_from_slice(&window.x.to_be_bytes());
+                            data.extend_from_slice(&window.y.to_be_bytes());
+                            data.extend_from_slice(&window.width.to_be_bytes());
+                            data.extend_from_slice(&window.height.to_be_bytes());
                         }
                         data
                     },
-                    DisplayCommand::SendPureText { window_id, text, color } => {
+                    DisplayCommand::SendPureText { window_id, text, color, font, effect, speed } => {
                         let mut data = vec![0x7b, 0x01]; // Command + Response request
                         
                         // Data length
                         let text_bytes = text.as_bytes();
-                        let data_length = 3 + 7 + text_bytes.len(); // subcommand + params + text length
+                        let data_length = 11 + text_bytes.len() + 1; // subcommand + params + text + null
                         data.extend_from_slice(&(data_length as u16).to_le_bytes());
                         
                         data.extend_from_slice(&[0x00, 0x00]); // Packet ID, Max Packet ID
                         data.push(0x12); // Subcommand: Send Pure Text
                         data.push(*window_id); // Target window
-                        data.push(0x00); // Display mode (instant)
-                        data.push(0x04); // Alignment (centered vertically, left justified)
-                        data.push(0x01); // Speed (fastest)
+                        data.push(*effect); // Display mode
+                        data.push(DEFAULT_ALIGNMENT); // Alignment (centered vertically, left justified)
+                        data.push(*speed); // Speed (1 is fastest)
                         data.extend_from_slice(&[0x00, 0x00]); // Display time (permanent)
-                        data.push(0x02); // Font size
+                        data.push(*font); // Font style and size
                         data.push(color.red);
                         data.push(color.green);
                         data.push(color.blue);
@@ -147,22 +200,117 @@ impl Command {
                         data.push(0x02); // Font size
                         data
                     },
+                    // The first of the packets the image is sent in
+                    DisplayCommand::SendImage { .. } => self.encode_packets().swap_remove(0),
                     _ => vec![], // Other display commands not implemented yet
                 }
             },
             _ => vec![],
         }
     }
+
+    /// Encode command into the data of each packet it is sent in. Images
+    /// are split into chunks of at most 500 bytes, numbered from 0, and
+    /// everything else fits in one packet.
+    pub fn encode_packets(&self) -> Vec<Vec<u8>> {
+        let Command::DisplayMessage(DisplayCommand::SendImage { window_id, mode, speed, image }) = self else {
+            return vec![self.encode()];
+        };
+
+        let mut header = vec![0x03, *window_id, *mode, *speed];
+        header.extend_from_slice(&[0x00, 0x00]); // Display time (permanent)
+        header.push(SIMPLE_PICTURE_FORMAT);
+        header.extend_from_slice(&image.x.to_be_bytes());
+        header.extend_from_slice(&image.y.to_be_bytes());
+
+        let picture = image.picture.encode();
+        let chunks: Vec<&[u8]> = picture.chunks(IMAGE_CHUNK_SIZE).collect();
+        let last_packet = (chunks.len() - 1) as u8;
+        chunks.iter()
+            .enumerate()
+            .map(|(packet, chunk)| {
+                // Only the first packet has the subcommand and its parameters
+                let body = if packet == 0 { [header.as_slice(), chunk].concat() } else { chunk.to_vec() };
+                let mut data = vec![0x7b, 0x01]; // Command + Response request
+                data.extend_from_slice(&(body.len() as u16).to_le_bytes());
+                data.extend_from_slice(&[packet as u8, last_packet]);
+                data.extend_from_slice(&body);
+                data
+            })
+            .collect()
+    }
+}
+
+/// Firmware versions reported by the Query Version Info (0x4b) command
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct VersionInfo {
+    pub boot: u16,
+    pub nxp: u16,
+    pub bios: u16,
+    pub app: u16,
+    pub net: u16,
+    pub logic: u16,
+}
+
+impl VersionInfo {
+    /// Parse the command data of a Query Version Info response
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        // Command, acknowledge, success flag, then the version table
+        if data.len() < 29 || data[0] != 0x4b || data[2] != 0x01 {
+            return None;
+        }
+        let version = |offset: usize| u16::from_le_bytes([data[3 + offset], data[4 + offset]]);
+        Some(Self {
+            boot: version(0),
+            nxp: version(4),
+            bios: version(8),
+            app: version(12),
+            net: version(20),
+            logic: version(24),
+        })
+    }
+
+    /// Encode as the command data of a Query Version Info response
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = vec![0x4b, 0x01, 0x01];
+        data.extend_from_slice(&[0u8; 30]);
+        for (offset, version) in [
+            (0, self.boot),
+            (4, self.nxp),
+            (8, self.bios),
+            (12, self.app),
+            (20, self.net),
+            (24, self.logic),
+        ] {
+            data[3 + offset..5 + offset].copy_from_slice(&version.to_le_bytes());
+        }
+        data
+    }
+
+    /// Application firmware version formatted as major.minor
+    pub fn app_version(&self) -> String {
+        format!("{}.{}", self.app >> 8, self.app & 0xff)
+    }
 }
 
 /// Common scoreboard window layout for football/rugby
-#[derive(Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ScoreboardLayout {
     pub home_name: WindowData,
     pub home_score: WindowData, 
     pub away_name: WindowData,
     pub away_score: WindowData,
     pub timer: WindowData,
+    /// Window for the conversion shot clock. Without one the shot clock is
+    /// shown in the timer window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shot_clock: Option<WindowData>,
+    /// Windows next to the team names for card indicators. Without them
+    /// cards aren't shown on the board.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_cards: Option<WindowData>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub away_cards: Option<WindowData>,
 }
 
 impl ScoreboardLayout {
@@ -174,18 +322,117 @@ impl ScoreboardLayout {
             away_name: WindowData { x: 0, y: 16, width: 96, height: 16 },
             away_score: WindowData { x: 96, y: 16, width: 32, height: 16 },
             timer: WindowData { x: 128, y: 0, width: 96, height: 32 },
+            shot_clock: None,
+            home_cards: None,
+            away_cards: None,
+        }
+    }
+
+    /// Window with the given ID from [`windows`]
+    pub fn window(&self, window_id: u8) -> Option<WindowData> {
+        match window_id {
+            windows::HOME_NAME => Some(self.home_name.clone()),
+            windows::HOME_SCORE => Some(self.home_score.clone()),
+            windows::AWAY_NAME => Some(self.away_name.clone()),
+            windows::AWAY_SCORE => Some(self.away_score.clone()),
+            windows::TIMER => Some(self.timer.clone()),
+            windows::SHOT_CLOCK => self.shot_clock.clone(),
+            windows::HOME_CARDS => self.home_cards.clone(),
+            windows::AWAY_CARDS => self.away_cards.clone(),
+            windows::FULLSCREEN => Some(self.fullscreen()),
+            _ => None,
+        }
+    }
+
+    /// Position of a window among those created, which is how the card
+    /// numbers them when optional windows are left out. The full screen
+    /// window is created on its own.
+    pub fn window_index(&self, window_id: u8) -> Option<u8> {
+        if window_id == windows::FULLSCREEN {
+            return Some(0);
+        }
+        self.window(window_id)?;
+        Some((0..window_id).filter(|id| self.window(*id).is_some()).count() as u8)
+    }
+
+    /// A single window covering every window of the layout
+    pub fn fullscreen(&self) -> WindowData {
+        let windows = self.all_windows();
+        WindowData {
+            x: 0,
+            y: 0,
+            width: windows.iter().map(|w| w.x + w.width).max().unwrap_or(0),
+            height: windows.iter().map(|w| w.y + w.height).max().unwrap_or(0),
         }
     }
 
     /// Get all windows as a vector for creating
     pub fn all_windows(&self) -> Vec<WindowData> {
-        vec![
+        let mut windows = vec![
             self.home_name.clone(),
             self.home_score.clone(),
             self.away_name.clone(),
             self.away_score.clone(),
             self.timer.clone(),
-        ]
+        ];
+        windows.extend(self.shot_clock.clone());
+        windows.extend(self.home_cards.clone());
+        windows.extend(self.away_cards.clone());
+        windows
+    }
+}
+
+/// Display / transition effects (appendix 2 of the protocol)
+pub mod effects {
+    pub const INSTANT: u8 = 0x00;
+    pub const SCROLL_LEFT: u8 = 0x0b;
+    pub const FLICKER: u8 = 0x0d;
+    pub const CONTINUOUS_SCROLL_LEFT: u8 = 0x0e;
+
+    /// Every effect by the name used in the API
+    pub const NAMES: [(&str, u8); 37] = [
+        ("instant", INSTANT),
+        ("open_left", 0x01),
+        ("open_right", 0x02),
+        ("open_horizontal", 0x03),
+        ("open_vertical", 0x04),
+        ("vertical_shutter", 0x05),
+        ("move_left", 0x06),
+        ("move_right", 0x07),
+        ("move_up", 0x08),
+        ("move_down", 0x09),
+        ("scroll_up", 0x0a),
+        ("scroll_left", SCROLL_LEFT),
+        ("scroll_right", 0x0c),
+        ("flicker", FLICKER),
+        ("continuous_scroll_left", CONTINUOUS_SCROLL_LEFT),
+        ("continuous_scroll_right", 0x0f),
+        ("horizontal_shutter", 0x10),
+        ("clockwise_open", 0x11),
+        ("anticlockwise_open", 0x12),
+        ("clockwise_windmill", 0x13),
+        ("anticlockwise_windmill", 0x14),
+        ("increasing_rectangle", 0x15),
+        ("decreasing_rectangle", 0x16),
+        ("increasing_diamond", 0x17),
+        ("decreasing_diamond", 0x18),
+        ("increasing_circle", 0x19),
+        ("decreasing_circle", 0x1a),
+        ("open_top_left", 0x1b),
+        ("open_top_right", 0x1c),
+        ("open_bottom_left", 0x1d),
+        ("open_bottom_right", 0x1e),
+        ("open_top_left_bottom_right", 0x1f),
+        ("open_top_right_bottom_left", 0x20),
+        ("slide_top_left", 0x21),
+        ("slide_top_right", 0x22),
+        ("slide_bottom_left", 0x23),
+        ("slide_bottom_right", 0x24),
+    ];
+
+    /// Look up an effect by name
+    pub fn by_name(name: &str) -> Option<u8> {
+        NAMES.iter().find(|(effect, _)| *effect == name).map(|(_, value)| *value)
     }
 }
 
@@ -196,4 +443,112 @@ pub mod windows {
     pub const AWAY_NAME: u8 = 2;
     pub const AWAY_SCORE: u8 = 3;
     pub const TIMER: u8 = 4;
-}
\ No newline at end of file
+    /// Only present when the layout has a shot clock window
+    pub const SHOT_CLOCK: u8 = 5;
+    /// Only present when the layout has card windows
+    pub const HOME_CARDS: u8 = 6;
+    pub const AWAY_CARDS: u8 = 7;
+    /// The whole board, replacing every other window while it is shown
+    pub const FULLSCREEN: u8 = 0xff;
+
+    /// Every window of a layout by the name used in config files and the API
+    pub const NAMES: [(&str, u8); 8] = [
+        ("home_name", HOME_NAME),
+        ("home_score", HOME_SCORE),
+        ("away_name", AWAY_NAME),
+        ("away_score", AWAY_SCORE),
+        ("timer", TIMER),
+        ("shot_clock", SHOT_CLOCK),
+        ("home_cards", HOME_CARDS),
+        ("away_cards", AWAY_CARDS),
+    ];
+
+    /// Look up a window by name
+    pub fn by_name(name: &str) -> Option<u8> {
+        NAMES.iter().find(|(window, _)| *window == name).map(|(_, id)| *id)
+    }
+
+    /// Name of a window, the reverse of [`by_name`]
+    pub fn name(id: u8) -> Option<&'static str> {
+        NAMES.iter().find(|(_, window)| *window == id).map(|(name, _)| *name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::{EthernetPacket, Picture};
+
+    #[test]
+    fn test_color_hex_round_trip() {
+        let color = Color::from_hex("#1e90ff").unwrap();
+        assert_eq!(color, Color { red: 0x1e, green: 0x90, blue: 0xff });
+        assert_eq!(color.to_hex(), "#1e90ff");
+        assert_eq!(Color::from_hex("FFFFFF").unwrap(), Color::WHITE);
+        assert!(Color::from_hex("#12345").is_err());
+        assert!(Color::from_hex("#gg0000").is_err());
+    }
+
+    #[test]
+    fn test_create_windows_matches_protocol_example() {
+        // Split a 96 x 32 display into 2 windows
+        let command = Command::DisplayMessage(DisplayCommand::CreateWindows(vec![
+            WindowData { x: 0, y: 0, width: 48, height: 32 },
+            WindowData { x: 48, y: 0, width: 48, height: 32 },
+        ]));
+        let packet = EthernetPacket::new(0xff, command.encode()).encode();
+        assert_eq!(packet.as_ref(), &[
+            0xff, 0xff, 0xff, 0xff, 0x1d, 0x00, 0x00, 0x00, 0x68, 0x32, 0xff,
+            0x7b, 0x01, 0x12, 0x00, 0x00, 0x00, 0x01, 0x02,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x30, 0x00, 0x20,
+            0x00, 0x30, 0x00, 0x00, 0x00, 0x30, 0x00, 0x20,
+            0xfa, 0x02,
+        ]);
+    }
+
+    #[test]
+    fn test_optional_windows_are_numbered_in_creation_order() {
+        let mut layout = ScoreboardLayout::standard_224x32();
+        layout.home_cards = Some(WindowData { x: 84, y: 0, width: 12, height: 16 });
+        layout.away_cards = Some(WindowData { x: 84, y: 16, width: 12, height: 16 });
+        assert_eq!(layout.window_index(windows::TIMER), Some(4));
+        assert_eq!(layout.window_index(windows::SHOT_CLOCK), None);
+        assert_eq!(layout.window_index(windows::AWAY_CARDS), Some(6));
+        assert_eq!((layout.all_windows()[6].x, layout.all_windows()[6].y), (84, 16));
+        let fullscreen = layout.window(windows::FULLSCREEN).unwrap();
+        assert_eq!((fullscreen.width, fullscreen.height), (224, 32));
+        assert_eq!(windows::by_name("away_cards"), Some(windows::AWAY_CARDS));
+        assert_eq!(windows::name(windows::SHOT_CLOCK), Some("shot_clock"));
+        assert_eq!(effects::by_name("continuous_scroll_left"), Some(effects::CONTINUOUS_SCROLL_LEFT));
+    }
+
+    #[test]
+    fn test_images_are_split_into_packets() {
+        // 224 x 32 at 1 bit per colour is 8 + 3 * 28 * 32 = 2696 bytes
+        let picture = Picture { width: 224, height: 32, pixels: vec![Color::RED; 224 * 32] };
+        let command = Command::DisplayMessage(DisplayCommand::SendImage {
+            window_id: 0,
+            mode: effects::INSTANT,
+            speed: 1,
+            image: WindowImage { x: 0, y: 2, picture },
+        });
+        let packets = command.encode_packets();
+        assert_eq!(packets.len(), 6);
+        assert_eq!(&packets[0][..17], &[
+            0x7b, 0x01, 0xff, 0x01, 0x00, 0x05,
+            0x03, 0x00, 0x00, 0x01, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x02,
+        ]);
+        assert_eq!(&packets[0][17..19], &[0x31, 0x31]);
+        assert_eq!(&packets[5][..6], &[0x7b, 0x01, 0xc4, 0x00, 0x05, 0x05]);
+        assert_eq!(packets.iter().map(|packet| packet.len() - 6).sum::<usize>(), 11 + 2696);
+        assert_eq!(command.encode(), packets[0]);
+    }
+
+    #[test]
+    fn test_version_info_round_trip() {
+        let info = VersionInfo { boot: 1, nxp: 2, bios: 3, app: 0x0305, net: 4, logic: 5 };
+        let parsed = VersionInfo::parse(&info.encode()).unwrap();
+        assert_eq!(parsed, info);
+        assert_eq!(parsed.app_version(), "3.5");
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::time::{Duration, Instant, timeout};
+use log::{info, debug};
+use anyhow::{Result, Context};
+use serde::Serialize;
+
+use crate::protocol::{EthernetPacket, Command, VersionInfo};
+
+/// Card ID that every control card accepts
+pub const BROADCAST_CARD_ID: u8 = 0xff;
+
+/// Port control cards normally listen on
+pub const DEFAULT_PORT: u16 = 5200;
+
+/// Port part of an `ip:port` scoreboard address, falling back to the default
+pub fn port_from_address(address: &str) -> u16 {
+    address.rsplit_once(':')
+        .and_then(|(_, port)| port.parse().ok())
+        .unwrap_or(DEFAULT_PORT)
+}
+
+/// A control card that answered a discovery probe
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredCard {
+    /// Address in the same `ip:port` form used by the scoreboard config
+    pub address: String,
+    pub ip: IpAddr,
+    pub card_id: u8,
+    /// Application firmware version, if the card answered the version query
+    pub firmware: Option<String>,
+    pub versions: Option<VersionInfo>,
+}
+
+/// Find CPower control cards on the local network.
+///
+/// A Query Version Info packet addressed to every card is broadcast over UDP
+/// on `port`. Every card that answers is then asked for its version over TCP,
+/// which also confirms the address can be used by the scoreboard client.
+pub async fn discover(port: u16, wait: Duration) -> Result<Vec<DiscoveredCard>> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await
+        .context("Failed to bind discovery socket")?;
+    socket.set_broadcast(true)?;
+
+    let probe = EthernetPacket::new(BROADCAST_CARD_ID, Command::QueryVersion.encode()).encode();
+    socket.send_to(&probe, (Ipv4Addr::BROADCAST, port)).await
+        .context("Failed to send discovery broadcast")?;
+    info!("Sent discovery broadcast on UDP port {}", port);
+
+    // Collect UDP replies until the wait period runs out
+    let mut replies: BTreeMap<IpAddr, Option<EthernetPacket>> = BTreeMap::new();
+    let deadline = Instant::now() + wait;
+    let mut buffer = vec![0u8; 1024];
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        match timeout(remaining, socket.recv_from(&mut buffer)).await {
+            Ok(Ok((n, from))) => {
+                debug!("Discovery reply from {}: {:02x?}", from, &buffer[..n]);
+                let packet = EthernetPacket::decode(&buffer[..n]).ok();
+                replies.entry(from.ip()).or_insert(packet);
+            }
+            Ok(Err(e)) => debug!("Discovery receive error: {}", e),
+            Err(_) => break,
+        }
+    }
+
+    let mut cards = Vec::new();
+    for (ip, packet) in replies {
+        let address = SocketAddr::new(ip, port).to_string();
+        let card = match query_card(&address, Duration::from_secs(2)).await {
+            Ok(card) => card,
+            Err(e) => {
+                debug!("Version query to {} failed: {}", address, e);
+                // Fall back to whatever the broadcast reply told us
+                let card_id = packet.as_ref().map(|p| p.card_id).unwrap_or(BROADCAST_CARD_ID);
+                let versions = packet.and_then(|p| VersionInfo::parse(&p.command_data));
+                DiscoveredCard {
+                    address,
+                    ip,
+                    card_id,
+                    firmware: versions.as_ref().map(VersionInfo::app_version),
+                    versions,
+                }
+            }
+        };
+        cards.push(card);
+    }
+
+    info!("Discovery found {} card(s)", cards.len());
+    Ok(cards)
+}
+
+/// Ask the card at `address` for its version over TCP
+pub async fn query_card(address: &str, wait: Duration) -> Result<DiscoveredCard> {
+    let socket_addr: SocketAddr = address.parse()
+        .with_context(|| format!("Invalid card address: {}", address))?;
+
+    let mut stream = timeout(wait, TcpStream::connect(socket_addr))
+        .await
+        .context("Connection timeout")??;
+
+    let packet = EthernetPacket::new(BROADCAST_CARD_ID, Command::QueryVersion.encode());
+    stream.write_all(&packet.encode()).await?;
+
+    let mut data = Vec::new();
+    let mut buffer = vec![0u8; 256];
+    let response = timeout(wait, async {
+        loop {
+            let n = stream.read(&mut buffer).await?;
+            if n == 0 {
+                anyhow::bail!("Connection closed by card");
+            }
+            data.extend_from_slice(&buffer[..n]);
+            if let Some(length) = EthernetPacket::frame_length(&data)
+                && data.len() >= length
+            {
+                return Ok(EthernetPacket::decode(&data)?);
+            }
+        }
+    })
+    .await
+    .context("Timeout waiting for version response")??;
+
+    let versions = VersionInfo::parse(&response.command_data);
+    Ok(DiscoveredCard {
+        address: address.to_string(),
+        ip: socket_addr.ip(),
+        card_id: response.card_id,
+        firmware: versions.as_ref().map(VersionInfo::app_version),
+        versions,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_query_card_reads_version() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = vec![0u8; 64];
+            let _ = socket.read(&mut buffer).await.unwrap();
+
+            let mut data = vec![0x4b, 0x01, 0x01];
+            data.extend_from_slice(&[0u8; 30]);
+            data[3 + 12] = 0x05; // App version 3.5
+            data[3 + 13] = 0x03;
+            let reply = EthernetPacket::response(7, data).encode();
+            socket.write_all(&reply).await.unwrap();
+        });
+
+        let card = query_card(&address, Duration::from_secs(2)).await.unwrap();
+        assert_eq!(card.card_id, 7);
+        assert_eq!(card.firmware.as_deref(), Some("3.5"));
+    }
+}
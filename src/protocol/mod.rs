@@ -1,7 +1,10 @@
 pub mod packet;
 pub mod commands;
 pub mod client;
+pub mod discovery;
+pub mod picture;
 
 pub use packet::*;
 pub use commands::*;
-pub use client::*;
\ No newline at end of file
+pub use client::*;
+pub use picture::*;
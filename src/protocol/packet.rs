@@ -15,7 +15,7 @@ pub struct EthernetPacket {
 impl EthernetPacket {
     /// Create a new packet for sending to the scoreboard
     pub fn new(card_id: u8, command_data: Vec<u8>) -> Self {
-        let data_length = 4 + command_data.len() as u32; // packet_type + card_type + card_id + command_data
+        let data_length = 5 + command_data.len() as u32; // packet_type + card_type + card_id + command_data + checksum
         
         Self {
             network_data_length: data_length,
@@ -27,9 +27,24 @@ impl EthernetPacket {
         }
     }
 
+    /// Create a response packet as sent back by the control card
+    pub fn response(card_id: u8, command_data: Vec<u8>) -> Self {
+        let mut packet = Self::new(card_id, command_data);
+        packet.packet_type = 0xe8;
+        packet
+    }
+
+    /// 16 bit sum of all bytes from packet type to the end of the command data
+    pub fn checksum(&self) -> u16 {
+        let header = [self.packet_type, self.card_type, self.card_id];
+        header.iter()
+            .chain(self.command_data.iter())
+            .fold(0u16, |sum, byte| sum.wrapping_add(*byte as u16))
+    }
+
     /// Encode the packet into bytes for transmission
     pub fn encode(&self) -> Bytes {
-        let mut buf = BytesMut::with_capacity(8 + self.command_data.len());
+        let mut buf = BytesMut::with_capacity(13 + self.command_data.len());
         
         // ID Code (4 bytes) - always 0xffffffff for packets
         buf.put_u32(0xffffffff);
@@ -51,13 +66,25 @@ impl EthernetPacket {
         
         // Command data
         buf.put_slice(&self.command_data);
+
+        // Checksum (2 bytes, little endian)
+        buf.put_u16_le(self.checksum());
         
         buf.freeze()
     }
 
+    /// Total number of bytes of the packet at the start of `data`, if the
+    /// header has been received
+    pub fn frame_length(data: &[u8]) -> Option<usize> {
+        if data.len() < 6 {
+            return None;
+        }
+        Some(8 + u16::from_le_bytes([data[4], data[5]]) as usize)
+    }
+
     /// Decode a packet from received bytes
     pub fn decode(data: &[u8]) -> io::Result<Self> {
-        if data.len() < 11 {
+        if data.len() < 13 {
             return Err(Error::new(ErrorKind::InvalidData, "Packet too short"));
         }
 
@@ -67,21 +94,29 @@ impl EthernetPacket {
         let packet_type = data[8];
         let card_type = data[9];
         let card_id = data[10];
+
+        let end = 8 + network_data_length as usize;
+        if network_data_length < 5 || data.len() < end {
+            return Err(Error::new(ErrorKind::InvalidData, "Packet length mismatch"));
+        }
         
-        let command_data = if data.len() > 11 {
-            data[11..].to_vec()
-        } else {
-            Vec::new()
-        };
+        let command_data = data[11..end - 2].to_vec();
+        let checksum = u16::from_le_bytes([data[end - 2], data[end - 1]]);
 
-        Ok(Self {
+        let packet = Self {
             network_data_length,
             reserved,
             packet_type,
             card_type,
             card_id,
             command_data,
-        })
+        };
+
+        if packet.checksum() != checksum {
+            return Err(Error::new(ErrorKind::InvalidData, "Packet checksum mismatch"));
+        }
+
+        Ok(packet)
     }
 
     /// Check if this is a response packet
@@ -107,4 +142,26 @@ mod tests {
         assert_eq!(decoded.packet_type, 0x68);
         assert_eq!(decoded.card_type, 0x32);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_packet_matches_protocol_example() {
+        // Restart hardware example from the CPower protocol document
+        let packet = EthernetPacket::new(0xff, vec![0x2d, 0x01, 0x00]);
+        let encoded = packet.encode();
+
+        assert_eq!(
+            encoded.as_ref(),
+            &[0xff, 0xff, 0xff, 0xff, 0x08, 0x00, 0x00, 0x00, 0x68, 0x32, 0xff, 0x2d, 0x01, 0x00, 0xc7, 0x01]
+        );
+        assert_eq!(EthernetPacket::frame_length(&encoded), Some(encoded.len()));
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_checksum() {
+        let mut encoded = EthernetPacket::new(0x01, vec![0x4b, 0x01, 0x00]).encode().to_vec();
+        let last = encoded.len() - 1;
+        encoded[last] ^= 0xff;
+
+        assert!(EthernetPacket::decode(&encoded).is_err());
+    }
+}
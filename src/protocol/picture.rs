@@ -0,0 +1,135 @@
+use anyhow::{Result, bail};
+
+use crate::protocol::Color;
+
+/// Most image data the card takes in one packet
+pub const IMAGE_CHUNK_SIZE: usize = 500;
+
+/// Image data format byte of the Send Image to Window subcommand for the
+/// simple picture format
+pub const SIMPLE_PICTURE_FORMAT: u8 = 0x04;
+
+/// An image in the card's simple picture format (appendix 4 of the
+/// protocol), with a pixel per LED
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Picture {
+    pub width: u16,
+    pub height: u16,
+    /// Pixels line by line, left to right and top to bottom
+    pub pixels: Vec<Color>,
+}
+
+/// A picture placed in a window, relative to its top left corner
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowImage {
+    pub x: u16,
+    pub y: u16,
+    pub picture: Picture,
+}
+
+impl Picture {
+    /// Colour of a pixel
+    pub fn pixel(&self, x: u16, y: u16) -> &Color {
+        &self.pixels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Encode with 1 bit per primary colour, each lit when it is at least
+    /// half on. Each line is padded to a whole byte, the leftmost pixel in
+    /// the top bit.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut data = vec![0x31, 0x31];
+        data.extend_from_slice(&self.width.to_le_bytes());
+        data.extend_from_slice(&self.height.to_le_bytes());
+        data.push(0x07); // Red, green and blue data at 1 bit per pixel
+        data.push(0x00);
+
+        let line_bytes = (self.width as usize).div_ceil(8);
+        let channels: [fn(&Color) -> u8; 3] = [|c| c.red, |c| c.green, |c| c.blue];
+        for channel in channels {
+            for line in self.pixels.chunks(self.width.max(1) as usize) {
+                let mut bytes = vec![0u8; line_bytes];
+                for (x, color) in line.iter().enumerate() {
+                    if channel(color) >= 0x80 {
+                        bytes[x / 8] |= 0x80 >> (x % 8);
+                    }
+                }
+                data.extend_from_slice(&bytes);
+            }
+        }
+        data
+    }
+
+    /// Decode 1 or 8 bit data, leaving out colours with no data block
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() < 8 || data[..2] != [0x31, 0x31] {
+            bail!("Not a simple picture");
+        }
+        let width = u16::from_le_bytes([data[2], data[3]]);
+        let height = u16::from_le_bytes([data[4], data[5]]);
+        let flags = data[6];
+        let eight_bit = match (flags >> 4) & 0x07 {
+            0 => false,
+            7 => true,
+            depth => bail!("Unsupported colour depth {}", depth),
+        };
+
+        let line_bytes = if eight_bit { width as usize } else { (width as usize).div_ceil(8) };
+        let block = line_bytes * height as usize;
+        let mut pixels = vec![Color::BLACK; width as usize * height as usize];
+        let mut blocks = data[8..].chunks(block.max(1));
+        for channel in 0..3 {
+            if flags & (1 << channel) == 0 {
+                continue;
+            }
+            let Some(values) = blocks.next().filter(|values| values.len() == block) else {
+                bail!("Picture data is too short for {}x{}", width, height);
+            };
+            for (index, pixel) in pixels.iter_mut().enumerate() {
+                let (x, y) = (index % width as usize, index / width as usize);
+                let value = if eight_bit {
+                    values[y * line_bytes + x]
+                } else if values[y * line_bytes + x / 8] & (0x80 >> (x % 8)) != 0 {
+                    0xff
+                } else {
+                    0x00
+                };
+                match channel {
+                    0 => pixel.red = value,
+                    1 => pixel.green = value,
+                    _ => pixel.blue = value,
+                }
+            }
+        }
+        if blocks.next().is_some() {
+            bail!("Picture has more data than {}x{}", width, height);
+        }
+        Ok(Self { width, height, pixels })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pictures_round_trip_at_one_bit() {
+        let picture = Picture {
+            width: 9,
+            height: 2,
+            pixels: (0..18)
+                .map(|index| match index % 3 {
+                    0 => Color::RED,
+                    1 => Color::YELLOW,
+                    _ => Color::BLACK,
+                })
+                .collect(),
+        };
+        let data = picture.encode();
+        // Header, then two bytes per 9 pixel line for each colour
+        assert_eq!(data.len(), 8 + 3 * 2 * 2);
+        assert_eq!(&data[..8], &[0x31, 0x31, 9, 0, 2, 0, 0x07, 0x00]);
+        assert_eq!(&data[8..10], &[0b1101_1011, 0b0000_0000]);
+        assert_eq!(Picture::decode(&data).unwrap(), picture);
+        assert!(Picture::decode(&data[..data.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use crate::config::RugbyConfig;
+
+/// One of the two teams in a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Home,
+    Away,
+}
+
+impl Side {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Side::Home => "home",
+            Side::Away => "away",
+        }
+    }
+}
+
+impl FromStr for Side {
+    type Err = anyhow::Error;
+
+    fn from_str(team: &str) -> anyhow::Result<Self> {
+        match team.to_lowercase().as_str() {
+            "home" => Ok(Side::Home),
+            "away" => Ok(Side::Away),
+            _ => Err(anyhow::anyhow!("Invalid team: {}", team)),
+        }
+    }
+}
+
+/// Scoring, timing and discipline rules of a code or sport
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleProfile {
+    pub name: String,
+    pub label: String,
+    /// Ways of scoring, in the order their buttons are shown
+    pub actions: Vec<ScoreAction>,
+    pub periods: u8,
+    pub period_minutes: u8,
+    /// Players on the field per team
+    #[serde(default = "default_players")]
+    pub players: u8,
+    /// Temporary suspensions, such as a yellow card's ten minutes
+    #[serde(default)]
+    pub sin_bins: Vec<SinBin>,
+    /// Minutes before a player sent off with a 20-minute red card may be
+    /// replaced, when the code allows one
+    #[serde(default)]
+    pub red_replacement_minutes: Option<u8>,
+}
+
+fn default_players() -> u8 {
+    15
+}
+
+/// A way of scoring and the points it is worth
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreAction {
+    pub name: String,
+    pub label: String,
+    pub points: u16,
+    /// Whether a conversion attempt follows
+    #[serde(default)]
+    pub converted: bool,
+    /// Whether it is the kick after a converted score, which stops the
+    /// shot clock
+    #[serde(default)]
+    pub conversion: bool,
+}
+
+/// A score action added to one profile, or every profile when `profile`
+/// is omitted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomAction {
+    #[serde(default)]
+    pub profile: Option<String>,
+    #[serde(flatten)]
+    pub action: ScoreAction,
+}
+
+/// A card that sends a player off for a while
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SinBin {
+    pub card: String,
+    pub minutes: u8,
+}
+
+impl RuleProfile {
+    /// Look up a sin bin by its card's name
+    pub fn sin_bin(&self, card: &str) -> Option<&SinBin> {
+        self.sin_bins.iter().find(|sin_bin| sin_bin.card == card)
+    }
+
+    /// Look up a score action by name
+    pub fn action(&self, name: &str) -> Option<&ScoreAction> {
+        self.actions.iter().find(|action| action.name == name)
+    }
+
+    /// Add a custom action, replacing any action of the same name
+    pub fn add_action(&mut self, action: ScoreAction) {
+        match self.actions.iter_mut().find(|existing| existing.name == action.name) {
+            Some(existing) => *existing = action,
+            None => self.actions.push(action),
+        }
+    }
+
+    /// The built-in profiles. Union takes its points and period length
+    /// from the `rugby` section of the config, and allows 20-minute red
+    /// cards.
+    pub fn builtin(rugby: &RugbyConfig) -> Vec<RuleProfile> {
+        vec![
+            RuleProfile {
+                red_replacement_minutes: Some(20),
+                ..profile("union", "Rugby Union", 2, rugby.period_minutes, 15, &[
+                    ("try", "Try", rugby.try_points, true),
+                    ("conversion", "Conversion", rugby.conversion_points, false),
+                    ("penalty", "Penalty", rugby.penalty_points, false),
+                    ("drop_goal", "Drop Goal", 3, false),
+                    ("penalty_try", "Penalty Try", 7, false),
+                ], &[("yellow", 10)])
+            },
+            profile("league", "Rugby League", 2, 40, 13, &[
+                ("try", "Try", 4, true),
+                ("conversion", "Conversion", 2, false),
+                ("penalty", "Penalty", 2, false),
+                ("drop_goal", "Drop Goal", 1, false),
+            ], &[("yellow", 10)]),
+            profile("sevens", "Rugby Sevens", 2, 7, 7, &[
+                ("try", "Try", 5, true),
+                ("conversion", "Conversion", 2, false),
+                ("penalty", "Penalty", 3, false),
+                ("drop_goal", "Drop Goal", 3, false),
+                ("penalty_try", "Penalty Try", 7, false),
+            ], &[("yellow", 2)]),
+            profile("tag", "Tag Rugby", 2, 20, 7, &[
+                ("try", "Try", 1, false),
+            ], &[]),
+            profile("football", "Football", 2, 45, 11, &[
+                ("goal", "Goal", 1, false),
+            ], &[]),
+            profile("hockey", "Hockey", 4, 15, 11, &[
+                ("goal", "Goal", 1, false),
+            ], &[("green", 2), ("yellow", 5)]),
+        ]
+    }
+}
+
+fn profile(
+    name: &str,
+    label: &str,
+    periods: u8,
+    period_minutes: u8,
+    players: u8,
+    actions: &[(&str, &str, u16, bool)],
+    sin_bins: &[(&str, u8)],
+) -> RuleProfile {
+    RuleProfile {
+        name: name.to_string(),
+        label: label.to_string(),
+        actions: actions.iter()
+            .map(|(name, label, points, converted)| ScoreAction {
+                name: name.to_string(),
+                label: label.to_string(),
+                points: *points,
+                converted: *converted,
+                // Every built-in profile calls the kick after a try a conversion
+                conversion: *name == "conversion",
+            })
+            .collect(),
+        periods,
+        period_minutes,
+        players,
+        sin_bins: sin_bins.iter()
+            .map(|(card, minutes)| SinBin { card: card.to_string(), minutes: *minutes })
+            .collect(),
+        red_replacement_minutes: None,
+    }
+}
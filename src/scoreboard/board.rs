@@ -0,0 +1,107 @@
+use tokio::sync::{mpsc, watch};
+use serde::Serialize;
+
+use crate::config::{BoardConfig, TextFitConfig};
+use crate::protocol::{ScoreboardClient, Command, ScoreboardLayout};
+use crate::scoreboard::ScoreboardState;
+use crate::scoreboard::writer::{BoardWriter, Intent};
+
+/// One physical board mirroring the shared scoreboard state. Updates are
+/// queued for the board's writer task, so none of these methods wait on
+/// the network.
+#[derive(Clone)]
+pub struct Board {
+    name: String,
+    layout: ScoreboardLayout,
+    intents: mpsc::UnboundedSender<Intent>,
+    status: watch::Receiver<BoardStatus>,
+}
+
+/// Connection status of a single board
+#[derive(Debug, Clone, Serialize)]
+pub struct BoardStatus {
+    pub name: String,
+    pub address: String,
+    pub card_id: u8,
+    pub connected: bool,
+}
+
+impl Board {
+    /// Create a board from its configuration, starting its writer task
+    /// unless in simulation mode
+    pub fn new(config: BoardConfig, text_fit: TextFitConfig, simulation_mode: bool) -> Self {
+        let (intents, receiver) = mpsc::unbounded_channel();
+        let (status_sender, status) = watch::channel(BoardStatus {
+            name: config.name.clone(),
+            address: config.address.clone(),
+            card_id: config.card_id,
+            connected: simulation_mode, // In simulation mode, always "connected"
+        });
+
+        if !simulation_mode {
+            let client = ScoreboardClient::new(config.address, config.card_id);
+            let writer = BoardWriter::new(config.name.clone(), config.layout.clone(), text_fit, client, receiver, status_sender);
+            tokio::spawn(writer.run());
+        }
+
+        Self {
+            name: config.name,
+            layout: config.layout,
+            intents,
+            status,
+        }
+    }
+
+    /// Name of the board from the config
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Window layout of the board
+    pub fn layout(&self) -> &ScoreboardLayout {
+        &self.layout
+    }
+
+    /// Check connection status
+    pub fn is_connected(&self) -> bool {
+        self.status.borrow().connected
+    }
+
+    /// Current connection status
+    pub fn status(&self) -> BoardStatus {
+        self.status.borrow().clone()
+    }
+
+    /// Connect, create the windows and show the current state
+    pub fn initialize(&self, state: &ScoreboardState) {
+        self.queue(Intent::Connect);
+        self.queue(Intent::Show(Box::new(state.clone())));
+    }
+
+    /// Show the state on the board
+    pub fn update_display(&self, state: &ScoreboardState) {
+        self.queue(Intent::Show(Box::new(state.clone())));
+    }
+
+    /// Send a single command to the board
+    pub fn send_command(&self, command: Command) {
+        self.queue(Intent::Command(command));
+    }
+
+    /// Reconnect and reinitialize the board if the connection was lost
+    pub fn ensure_connection(&self) {
+        if !self.is_connected() {
+            self.queue(Intent::Connect);
+        }
+    }
+
+    /// Point the board at a different control card
+    pub fn set_target(&self, address: String, card_id: u8) {
+        self.queue(Intent::Retarget { address, card_id });
+    }
+
+    fn queue(&self, intent: Intent) {
+        // Fails only in simulation mode, where there is no writer task
+        let _ = self.intents.send(intent);
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::{Result, anyhow, bail};
+
+use crate::config::CelebrationConfig;
+use crate::protocol::{Color, effects, windows};
+use crate::scoreboard::{BoardMessage, Side};
+
+/// The first celebration configured for a team scoring an action
+pub fn find<'a>(celebrations: &'a [CelebrationConfig], action: &str, team: Side) -> Option<&'a CelebrationConfig> {
+    celebrations.iter()
+        .find(|celebration| celebration.action == action && celebration.team.is_none_or(|side| side == team))
+}
+
+/// A celebration's frames for a team, to be shown in turn like messages
+pub fn frames(celebration: &CelebrationConfig, team_name: &str) -> Result<Vec<BoardMessage>> {
+    let window = match celebration.window.as_deref() {
+        Some(name) => Some(windows::by_name(name).ok_or_else(|| anyhow!("Unknown window: {}", name))?),
+        None => None,
+    };
+    celebration.frames.iter()
+        .zip(1..)
+        .map(|(frame, id)| {
+            if frame.seconds == 0 {
+                bail!("Frame {} of the {} celebration has to be shown for at least a second", id, celebration.action);
+            }
+            let effect = match frame.effect.as_deref() {
+                Some(name) => effects::by_name(name).ok_or_else(|| anyhow!("Unknown effect: {}", name))?,
+                None => effects::INSTANT,
+            };
+            Ok(BoardMessage {
+                id,
+                text: frame.text.replace("{team}", team_name),
+                seconds: frame.seconds,
+                window,
+                effect,
+                color: frame.color.clone().unwrap_or(Color::YELLOW),
+                image: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CelebrationFrame;
+
+    fn frame(text: &str, effect: Option<&str>) -> CelebrationFrame {
+        CelebrationFrame { text: text.to_string(), seconds: 2, effect: effect.map(str::to_string), color: None }
+    }
+
+    #[test]
+    fn test_celebrations_for_the_scoring_team() {
+        let celebrations = vec![
+            CelebrationConfig {
+                action: "try".to_string(),
+                team: Some(Side::Home),
+                window: None,
+                frames: vec![frame("TRY!", Some("flicker")), frame("{team}", None)],
+            },
+            CelebrationConfig {
+                action: "penalty".to_string(),
+                team: None,
+                window: Some("timer".to_string()),
+                frames: vec![frame("3 POINTS", Some("sparkle"))],
+            },
+        ];
+        assert!(find(&celebrations, "try", Side::Away).is_none());
+        assert!(find(&celebrations, "conversion", Side::Home).is_none());
+
+        let frames = frames(find(&celebrations, "try", Side::Home).unwrap(), "HARLEQUINS").unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!((frames[0].effect, frames[0].window), (effects::FLICKER, None));
+        assert_eq!(frames[1].text, "HARLEQUINS");
+
+        let penalty = find(&celebrations, "penalty", Side::Away).unwrap();
+        assert!(super::frames(penalty, "AWAY").is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::protocol::Color;
+use crate::scoreboard::Side;
+use crate::scoreboard::roster::Player;
+
+/// A red card, the player sent off for the rest of the match
+pub const RED: &str = "red";
+/// A red card after which the player may be replaced once the profile's
+/// replacement time is up
+pub const RED_20: &str = "red_20";
+
+/// A card shown to a player
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Card {
+    pub id: u32,
+    pub team: Side,
+    /// A sin bin's card, such as "yellow", or `red` or `red_20`
+    pub card: String,
+    pub player: Player,
+    /// Match clock when it was shown
+    pub minute: u8,
+    pub second: u8,
+    /// Seconds of match clock until the team is back to full strength,
+    /// none for a red card
+    pub remaining: Option<u16>,
+    pub recorded_at: DateTime<Local>,
+}
+
+impl Card {
+    /// Whether the team is still a player short for it
+    pub fn is_off(&self) -> bool {
+        self.remaining.is_none_or(|seconds| seconds > 0)
+    }
+
+    /// Name of the card for people, such as "Yellow" or "20-minute red"
+    pub fn label(&self) -> String {
+        if self.card == RED_20 {
+            return "20-minute red".to_string();
+        }
+        let mut chars = self.card.chars();
+        chars.next()
+            .map(|first| first.to_uppercase().chain(chars).collect())
+            .unwrap_or_default()
+    }
+
+    /// Colour of the card on the board
+    pub fn color(&self) -> Color {
+        match self.card.as_str() {
+            RED | RED_20 => Color::RED,
+            "yellow" => Color::YELLOW,
+            "green" => Color::GREEN,
+            _ => Color::WHITE,
+        }
+    }
+}
+
+/// Cards shown so far and how many players each team has on the field
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Discipline {
+    pub cards: Vec<Card>,
+    pub home_players: u8,
+    pub away_players: u8,
+}
+
+/// Players a team has on the field, of `players`, with its cards
+pub fn players_on_field(cards: &[Card], side: Side, players: u8) -> u8 {
+    let off = cards.iter().filter(|card| card.team == side && card.is_off()).count();
+    players.saturating_sub(off.min(u8::MAX as usize) as u8)
+}
+
+/// Count down the cards by a second of match clock, returning those whose
+/// time has just run out
+pub fn tick(cards: &mut [Card]) -> Vec<Card> {
+    let mut served = Vec::new();
+    for card in cards.iter_mut().filter(|card| card.is_off()) {
+        if let Some(seconds) = card.remaining.as_mut() {
+            *seconds -= 1;
+            if *seconds == 0 {
+                served.push(card.clone());
+            }
+        }
+    }
+    served
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(id: u32, team: Side, card: &str, remaining: Option<u16>) -> Card {
+        Card {
+            id,
+            team,
+            card: card.to_string(),
+            player: Player { number: id as u8, name: String::new() },
+            minute: 0,
+            second: 0,
+            remaining,
+            recorded_at: Local::now(),
+        }
+    }
+
+    #[test]
+    fn test_cards_leave_teams_short() {
+        let mut cards = vec![
+            card(1, Side::Home, "yellow", Some(2)),
+            card(2, Side::Home, RED, None),
+            card(3, Side::Away, RED_20, Some(1)),
+        ];
+        assert_eq!(players_on_field(&cards, Side::Home, 15), 13);
+        assert_eq!(players_on_field(&cards, Side::Away, 15), 14);
+
+        let served = tick(&mut cards);
+        assert_eq!(served.len(), 1);
+        assert_eq!(served[0].id, 3);
+        assert_eq!(players_on_field(&cards, Side::Away, 15), 15);
+        assert_eq!(tick(&mut cards)[0].id, 1);
+        assert!(tick(&mut cards).is_empty());
+        assert_eq!(players_on_field(&cards, Side::Home, 15), 14);
+        assert_eq!(cards[1].color(), Color::RED);
+        assert_eq!((cards[0].label().as_str(), cards[2].label().as_str()), ("Yellow", "20-minute red"));
+    }
+}
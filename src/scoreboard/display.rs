@@ -0,0 +1,331 @@
+use std::collections::HashMap;
+
+use crate::config::{FitPolicy, TextFitConfig};
+use crate::protocol::{Color, ScoreboardLayout, WindowData, WindowImage, DEFAULT_FONT, effects, font_points, windows};
+use crate::scoreboard::{BoardMessage, ScoreboardState, Side};
+use crate::scoreboard::font;
+
+/// Effect speed used when text has to scroll
+const SCROLL_SPEED: u8 = 4;
+
+/// Text and colour shown in a single window, with how the card draws it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowContent {
+    pub text: String,
+    pub color: Color,
+    pub font: u8,
+    pub effect: u8,
+    pub speed: u8,
+    /// Picture sent in place of the text
+    pub image: Option<WindowImage>,
+}
+
+impl WindowContent {
+    /// Text shown instantly in the default font
+    pub fn new(text: impl Into<String>, color: Color) -> Self {
+        Self {
+            text: text.into(),
+            color,
+            font: DEFAULT_FONT,
+            effect: effects::INSTANT,
+            speed: 1,
+            image: None,
+        }
+    }
+
+    /// Whether the text fits a window of the given width in its font
+    pub fn fits(&self, width: u16) -> bool {
+        font::text_width(&self.text, font_points(self.font)) <= width
+    }
+
+    /// The same text scrolling continuously through the window
+    pub fn scrolling(self) -> Self {
+        Self {
+            effect: effects::CONTINUOUS_SCROLL_LEFT,
+            speed: SCROLL_SPEED,
+            ..self
+        }
+    }
+}
+
+/// Content of every window for the given state, fitted to the layout
+pub fn window_contents(
+    state: &ScoreboardState,
+    layout: &ScoreboardLayout,
+    text_fit: &TextFitConfig,
+) -> Vec<(u8, WindowContent)> {
+    let message = state.celebration.first().or(state.messages.first()).or(state.sponsor.as_ref());
+    if let Some(message) = message.filter(|message| message.window.is_none()) {
+        return vec![(windows::FULLSCREEN, message_content(message, &layout.fullscreen()))];
+    }
+
+    let timer_text = format!("{:02}:{:02}", state.timer_minutes, state.timer_seconds);
+    let timer_color = if state.timer_running { Color::RED } else { Color::WHITE };
+    let shot_clock = state.shot_clock
+        .map(|seconds| WindowContent::new(format!("{}:{:02}", seconds / 60, seconds % 60), Color::YELLOW));
+
+    // The shot clock has its own window when the layout has one, and
+    // otherwise takes over the timer window while it runs
+    let timer = match (&shot_clock, &layout.shot_clock) {
+        (Some(shot_clock), None) => shot_clock.clone(),
+        _ => WindowContent::new(timer_text, timer_color),
+    };
+
+    let score = |score: u16| if state.half_time_label {
+        WindowContent::new("HT", Color::YELLOW)
+    } else {
+        WindowContent::new(score.to_string(), Color::GREEN)
+    };
+
+    let (home_name, home_short_name) = name_content(state, Side::Home);
+    let (away_name, away_short_name) = name_content(state, Side::Away);
+
+    let mut contents = vec![
+        (windows::HOME_NAME, home_name, home_short_name),
+        (windows::AWAY_NAME, away_name, away_short_name),
+        (windows::HOME_SCORE, score(state.home_score), None),
+        (windows::AWAY_SCORE, score(state.away_score), None),
+        (windows::TIMER, timer, None),
+    ];
+    if layout.shot_clock.is_some() {
+        let shot_clock = shot_clock.unwrap_or_else(|| WindowContent::new("", Color::YELLOW));
+        contents.push((windows::SHOT_CLOCK, shot_clock, None));
+    }
+    if layout.home_cards.is_some() {
+        contents.push((windows::HOME_CARDS, cards_content(state, Side::Home), None));
+    }
+    if layout.away_cards.is_some() {
+        contents.push((windows::AWAY_CARDS, cards_content(state, Side::Away), None));
+    }
+
+    contents
+        .into_iter()
+        .map(|(window_id, content, short_name)| {
+            let content = match (layout.window(window_id), message) {
+                (Some(window), Some(message)) if message.window == Some(window_id) => message_content(message, &window),
+                (Some(window), _) => fit_text(content, window.width, text_fit.policy(window_id), short_name),
+                (None, _) => content,
+            };
+            (window_id, content)
+        })
+        .collect()
+}
+
+/// A team's name and short name, or for a few seconds after a score its
+/// scorer in their place
+fn name_content(state: &ScoreboardState, side: Side) -> (WindowContent, Option<&str>) {
+    if let Some(flash) = state.scorer_flash.as_ref().filter(|flash| flash.team == side) {
+        return (WindowContent::new(flash.text.clone(), Color::YELLOW), None);
+    }
+    match side {
+        Side::Home => (WindowContent::new(state.home_team.clone(), state.home_color.clone()), state.home_short_name.as_deref()),
+        Side::Away => (WindowContent::new(state.away_team.clone(), state.away_color.clone()), state.away_short_name.as_deref()),
+    }
+}
+
+/// A message with its effect, scrolling through the window when it is
+/// meant to be shown still but doesn't fit, or its image fitted to the
+/// window
+fn message_content(message: &BoardMessage, window: &WindowData) -> WindowContent {
+    let content = WindowContent {
+        effect: message.effect,
+        speed: if message.effect == effects::INSTANT { 1 } else { SCROLL_SPEED },
+        image: message.image.as_ref().map(|image| image.fit(window)),
+        ..WindowContent::new(message.text.clone(), message.color.clone())
+    };
+    if content.effect == effects::INSTANT && content.image.is_none() && !content.fits(window.width) {
+        content.scrolling()
+    } else {
+        content
+    }
+}
+
+/// A bar for each of a team's players off the field for a card, in the
+/// colour of the most serious card
+fn cards_content(state: &ScoreboardState, side: Side) -> WindowContent {
+    let off: Vec<_> = state.cards.iter().filter(|card| card.team == side && card.is_off()).collect();
+    let color = off.iter()
+        .map(|card| card.color())
+        .find(|color| *color == Color::RED)
+        .or_else(|| off.last().map(|card| card.color()))
+        .unwrap_or(Color::WHITE);
+    WindowContent::new("|".repeat(off.len()), color)
+}
+
+/// Make text fit a window of the given width according to the policy
+pub fn fit_text(content: WindowContent, width: u16, policy: FitPolicy, short_name: Option<&str>) -> WindowContent {
+    if content.fits(width) {
+        return content;
+    }
+
+    match policy {
+        FitPolicy::Clip => content,
+        FitPolicy::Shrink => {
+            // Keep the font style, try each smaller size
+            let style = content.font & 0x70;
+            (0..content.font & 0x07)
+                .rev()
+                .map(|size| WindowContent { font: style | size, ..content.clone() })
+                .find(|smaller| smaller.fits(width))
+                .unwrap_or_else(|| content.scrolling())
+        }
+        FitPolicy::ShortName => {
+            let short = WindowContent {
+                text: short_name.unwrap_or(&content.text).to_string(),
+                ..content
+            };
+            if short.fits(width) {
+                return short;
+            }
+            // Abbreviate to as many characters as fit
+            let max_chars = width / font::char_width(font_points(short.font));
+            let text = short.text.chars().take(max_chars as usize).collect();
+            WindowContent { text, ..short }
+        }
+        FitPolicy::Scroll => content.scrolling(),
+    }
+}
+
+/// Windows whose wanted content differs from what was last sent
+pub fn changed_windows(
+    sent: &HashMap<u8, WindowContent>,
+    wanted: Vec<(u8, WindowContent)>,
+) -> Vec<(u8, WindowContent)> {
+    wanted
+        .into_iter()
+        .filter(|(window_id, content)| sent.get(window_id) != Some(content))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contents(state: &ScoreboardState) -> Vec<(u8, WindowContent)> {
+        window_contents(state, &ScoreboardLayout::standard_224x32(), &TextFitConfig::default())
+    }
+
+    #[test]
+    fn test_only_changed_windows_are_resent() {
+        let mut state = ScoreboardState::default();
+        let sent: HashMap<u8, WindowContent> = contents(&state).into_iter().collect();
+
+        state.home_score = 5;
+        let changed = changed_windows(&sent, contents(&state));
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].0, windows::HOME_SCORE);
+        assert_eq!(changed[0].1.text, "5");
+
+        state.timer_running = true;
+        let changed = changed_windows(&sent, contents(&state));
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[1].0, windows::TIMER);
+        assert_eq!(changed[1].1.color, Color::RED);
+    }
+
+    #[test]
+    fn test_everything_sent_when_nothing_known() {
+        let state = ScoreboardState::default();
+        assert_eq!(changed_windows(&HashMap::new(), contents(&state)).len(), 5);
+    }
+
+    #[test]
+    fn test_shot_clock_window() {
+        let state = ScoreboardState { shot_clock: Some(90), ..ScoreboardState::default() };
+        let timer = |contents: Vec<(u8, WindowContent)>| {
+            contents.into_iter().find(|(id, _)| *id == windows::TIMER).unwrap().1
+        };
+
+        // Shown in the timer window without a window of its own
+        let shown = contents(&state);
+        assert_eq!(timer(shown.clone()).text, "1:30");
+        assert_eq!(shown.len(), 5);
+
+        let mut layout = ScoreboardLayout::standard_224x32();
+        layout.shot_clock = Some(crate::protocol::WindowData { x: 224, y: 0, width: 32, height: 32 });
+        let shown = window_contents(&state, &layout, &TextFitConfig::default());
+        assert_eq!(timer(shown.clone()).text, "00:00");
+        assert_eq!(shown[5], (windows::SHOT_CLOCK, WindowContent::new("1:30", Color::YELLOW)));
+    }
+
+    #[test]
+    fn test_half_time_label_replaces_scores() {
+        let state = ScoreboardState { home_score: 10, half_time_label: true, ..ScoreboardState::default() };
+        let shown = contents(&state);
+        assert!(shown.contains(&(windows::HOME_SCORE, WindowContent::new("HT", Color::YELLOW))));
+        assert!(shown.contains(&(windows::AWAY_SCORE, WindowContent::new("HT", Color::YELLOW))));
+    }
+
+    #[test]
+    fn test_card_indicators_next_to_names() {
+        let card = |team, card: &str, remaining| crate::scoreboard::Card {
+            id: 1,
+            team,
+            card: card.to_string(),
+            player: crate::scoreboard::Player { number: 6, name: String::new() },
+            minute: 0,
+            second: 0,
+            remaining,
+            recorded_at: chrono::Local::now(),
+        };
+        let state = ScoreboardState {
+            cards: vec![card(Side::Home, "yellow", Some(60)), card(Side::Home, "red", None), card(Side::Away, "yellow", Some(0))],
+            ..ScoreboardState::default()
+        };
+        assert_eq!(contents(&state).len(), 5);
+
+        let mut layout = ScoreboardLayout::standard_224x32();
+        layout.home_cards = Some(crate::protocol::WindowData { x: 84, y: 0, width: 12, height: 16 });
+        layout.away_cards = Some(crate::protocol::WindowData { x: 84, y: 16, width: 12, height: 16 });
+        let shown = window_contents(&state, &layout, &TextFitConfig::default());
+        assert!(shown.contains(&(windows::HOME_CARDS, WindowContent::new("||", Color::RED))));
+        assert!(shown.contains(&(windows::AWAY_CARDS, WindowContent::new("", Color::WHITE))));
+    }
+
+    #[test]
+    fn test_messages_replace_windows() {
+        let message = |window| BoardMessage {
+            id: 1,
+            text: "BAR OPEN AFTER THE MATCH".to_string(),
+            seconds: 10,
+            window,
+            effect: effects::INSTANT,
+            color: Color::YELLOW,
+            image: None,
+        };
+        let state = ScoreboardState { messages: vec![message(None)], ..ScoreboardState::default() };
+        let shown = contents(&state);
+        assert_eq!(shown.len(), 1);
+        assert_eq!(shown[0].0, windows::FULLSCREEN);
+        // Too long even for the whole board
+        assert_eq!(shown[0].1.effect, effects::CONTINUOUS_SCROLL_LEFT);
+
+        let state = ScoreboardState { messages: vec![message(Some(windows::TIMER))], ..ScoreboardState::default() };
+        let shown = contents(&state);
+        assert_eq!(shown.len(), 5);
+        let timer = &shown.iter().find(|(id, _)| *id == windows::TIMER).unwrap().1;
+        assert_eq!((timer.text.as_str(), timer.effect), ("BAR OPEN AFTER THE MATCH", effects::CONTINUOUS_SCROLL_LEFT));
+    }
+
+    #[test]
+    fn test_fit_policies() {
+        // 16 point characters are 12 pixels wide, so 8 fit in 96 pixels
+        let name = || WindowContent::new("BASINGSTOKE", Color::WHITE);
+
+        assert_eq!(fit_text(name(), 96, FitPolicy::Clip, None), name());
+
+        let shrunk = fit_text(name(), 96, FitPolicy::Shrink, None);
+        assert_eq!(shrunk.font, 0x00);
+        assert_eq!(shrunk.text, "BASINGSTOKE");
+
+        assert_eq!(fit_text(name(), 96, FitPolicy::ShortName, Some("BASING")).text, "BASING");
+        assert_eq!(fit_text(name(), 96, FitPolicy::ShortName, None).text, "BASINGST");
+
+        let scrolling = fit_text(name(), 96, FitPolicy::Scroll, None);
+        assert_eq!(scrolling.effect, effects::CONTINUOUS_SCROLL_LEFT);
+
+        // Names that already fit are left alone
+        let short = WindowContent::new("HRUFC", Color::WHITE);
+        assert_eq!(fit_text(short.clone(), 96, FitPolicy::Scroll, None), short);
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::rules::{ScoreAction, Side};
+use crate::scoreboard::roster::Player;
+use crate::scoreboard::substitutions::Substitution;
+
+/// A score recorded during the match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreEvent {
+    pub id: u32,
+    pub team: Side,
+    /// Name of the profile's score action
+    pub action: String,
+    pub label: String,
+    pub points: u16,
+    /// Match clock when it was scored
+    pub minute: u8,
+    pub second: u8,
+    /// Who scored, when the operator gave a shirt number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<Player>,
+    pub recorded_at: DateTime<Local>,
+}
+
+/// Scores recorded so far, in the order they happened
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: Vec<ScoreEvent>,
+    next_id: u32,
+}
+
+impl EventLog {
+    /// Record a score, giving it the next ID
+    pub fn record(&mut self, team: Side, action: &ScoreAction, minute: u8, second: u8, player: Option<Player>) -> ScoreEvent {
+        self.next_id += 1;
+        let event = ScoreEvent {
+            id: self.next_id,
+            team,
+            action: action.name.clone(),
+            label: action.label.clone(),
+            points: action.points,
+            minute,
+            second,
+            player,
+            recorded_at: Local::now(),
+        };
+        self.events.push(event.clone());
+        event
+    }
+
+    /// Take back the latest score of an action by a team
+    pub fn remove_last(&mut self, team: Side, action: &str) -> Option<ScoreEvent> {
+        let index = self.events.iter().rposition(|event| event.team == team && event.action == action)?;
+        Some(self.events.remove(index))
+    }
+
+    /// Take back the latest score
+    pub fn pop(&mut self) -> Option<ScoreEvent> {
+        self.events.pop()
+    }
+
+    pub fn events(&self) -> &[ScoreEvent] {
+        &self.events
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+/// Something that happened during the match, for the match's timeline
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "entry", rename_all = "lowercase")]
+pub enum TimelineEntry {
+    Score(ScoreEvent),
+    Substitution(Substitution),
+    /// A temporary replacement whose time for the player to return ran out
+    Expired(Substitution),
+}
+
+impl TimelineEntry {
+    /// Match clock when it happened
+    pub fn clock(&self) -> (u8, u8) {
+        match self {
+            TimelineEntry::Score(event) => (event.minute, event.second),
+            TimelineEntry::Substitution(substitution) => (substitution.minute, substitution.second),
+            TimelineEntry::Expired(substitution) => (
+                substitution.expired_minute.unwrap_or(substitution.minute),
+                substitution.expired_second.unwrap_or(substitution.second),
+            ),
+        }
+    }
+}
+
+/// Scores and substitutions merged in match clock order
+pub fn timeline(events: &[ScoreEvent], substitutions: &[Substitution]) -> Vec<TimelineEntry> {
+    let mut timeline: Vec<TimelineEntry> = events.iter().cloned().map(TimelineEntry::Score).collect();
+    for substitution in substitutions {
+        timeline.push(TimelineEntry::Substitution(substitution.clone()));
+        if substitution.expired_minute.is_some() {
+            timeline.push(TimelineEntry::Expired(substitution.clone()));
+        }
+    }
+    timeline.sort_by_key(TimelineEntry::clock);
+    timeline
+}
@@ -0,0 +1,211 @@
+use anyhow::{Context, Result, bail};
+
+use crate::protocol::{Color, Picture, WindowData, WindowImage};
+
+/// Widest and tallest an uploaded image is kept at, well beyond any board
+const MAX_SIZE: u32 = 512;
+
+/// An uploaded image, such as a crest or a sponsor's logo
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    width: u32,
+    height: u32,
+    /// Pixels line by line, left to right and top to bottom
+    pixels: Vec<Color>,
+}
+
+impl Image {
+    /// Decode a PNG or an uncompressed 24 or 32-bit BMP. Transparent parts
+    /// are left dark, and large images are shrunk to a size boards can use.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let image = if data.starts_with(b"\x89PNG") {
+            decode_png(data).context("Invalid PNG")?
+        } else if data.starts_with(b"BM") {
+            decode_bmp(data).context("Invalid BMP")?
+        } else {
+            bail!("Images have to be PNG or BMP");
+        };
+        if image.width == 0 || image.height == 0 {
+            bail!("The image is empty");
+        }
+        if image.width > MAX_SIZE || image.height > MAX_SIZE {
+            return Ok(image.scaled(MAX_SIZE, MAX_SIZE));
+        }
+        Ok(image)
+    }
+
+    /// Load an image file
+    pub fn load(path: &str) -> Result<Self> {
+        let data = std::fs::read(path).with_context(|| format!("Failed to read {}", path))?;
+        Self::decode(&data).with_context(|| format!("Failed to load {}", path))
+    }
+
+    /// The image scaled to fit a window, keeping its shape, reduced to the
+    /// eight colours of the card's simple picture format and centred
+    pub fn fit(&self, window: &WindowData) -> WindowImage {
+        let scaled = self.scaled(window.width as u32, window.height as u32);
+        let pixels = scaled.pixels.iter()
+            .map(|color| {
+                let level = |value: u8| if value >= 0x80 { 0xff } else { 0x00 };
+                Color { red: level(color.red), green: level(color.green), blue: level(color.blue) }
+            })
+            .collect();
+        WindowImage {
+            x: ((window.width as u32 - scaled.width) / 2) as u16,
+            y: ((window.height as u32 - scaled.height) / 2) as u16,
+            picture: Picture { width: scaled.width as u16, height: scaled.height as u16, pixels },
+        }
+    }
+
+    /// The image scaled to fit a width and height, keeping its shape, each
+    /// pixel the average of those it covers. Images that already fit are
+    /// only grown when needed.
+    fn scaled(&self, width: u32, height: u32) -> Self {
+        let scale = (width as f64 / self.width as f64).min(height as f64 / self.height as f64);
+        let target_width = ((self.width as f64 * scale).round() as u32).clamp(1, width.max(1));
+        let target_height = ((self.height as f64 * scale).round() as u32).clamp(1, height.max(1));
+        if (target_width, target_height) == (self.width, self.height) {
+            return self.clone();
+        }
+
+        // Source pixels covered by target pixel `index` of `target` across `source`
+        let span = |index: u32, source: u32, target: u32| {
+            let start = index * source / target;
+            let end = ((index + 1) * source / target).max(start + 1);
+            start..end
+        };
+        let mut pixels = Vec::with_capacity((target_width * target_height) as usize);
+        for y in 0..target_height {
+            for x in 0..target_width {
+                let (mut red, mut green, mut blue, mut count) = (0u32, 0u32, 0u32, 0u32);
+                for sy in span(y, self.height, target_height) {
+                    for sx in span(x, self.width, target_width) {
+                        let color = &self.pixels[(sy * self.width + sx) as usize];
+                        red += color.red as u32;
+                        green += color.green as u32;
+                        blue += color.blue as u32;
+                        count += 1;
+                    }
+                }
+                pixels.push(Color { red: (red / count) as u8, green: (green / count) as u8, blue: (blue / count) as u8 });
+            }
+        }
+        Self { width: target_width, height: target_height, pixels }
+    }
+}
+
+fn decode_png(data: &[u8]) -> Result<Image> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer)?;
+
+    let channels = match info.color_type {
+        png::ColorType::Grayscale => 1,
+        png::ColorType::GrayscaleAlpha => 2,
+        png::ColorType::Rgb => 3,
+        png::ColorType::Rgba => 4,
+        other => bail!("Unsupported colour type {:?}", other),
+    };
+    let pixels = buffer[..info.line_size * info.height as usize]
+        .chunks(info.line_size)
+        .flat_map(|line| line[..info.width as usize * channels].chunks(channels))
+        .map(|pixel| {
+            let (rgb, alpha) = match pixel {
+                [gray] => ([*gray; 3], 0xff),
+                [gray, alpha] => ([*gray; 3], *alpha),
+                [red, green, blue] => ([*red, *green, *blue], 0xff),
+                [red, green, blue, alpha] => ([*red, *green, *blue], *alpha),
+                _ => unreachable!(),
+            };
+            let over_black = |value: u8| (value as u32 * alpha as u32 / 0xff) as u8;
+            Color { red: over_black(rgb[0]), green: over_black(rgb[1]), blue: over_black(rgb[2]) }
+        })
+        .collect();
+    Ok(Image { width: info.width, height: info.height, pixels })
+}
+
+fn decode_bmp(data: &[u8]) -> Result<Image> {
+    if data.len() < 54 {
+        bail!("Too short");
+    }
+    let u16_at = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+    let u32_at = |offset: usize| u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+    let offset = u32_at(10) as usize;
+    let width = u32_at(18) as i32;
+    let height = u32_at(22) as i32;
+    let bits = u16_at(28);
+    // Uncompressed, or 32-bit with the usual BGRA bit fields
+    let compression = u32_at(30);
+    if !matches!((bits, compression), (24, 0) | (32, 0) | (32, 3)) {
+        bail!("Only uncompressed 24 and 32-bit images are supported");
+    }
+    if width <= 0 || height == 0 {
+        bail!("Invalid size {}x{}", width, height);
+    }
+
+    let (width, rows) = (width as u32, height.unsigned_abs());
+    let bytes = bits as usize / 8;
+    let stride = (width as usize * bytes).div_ceil(4) * 4;
+    if data.len() < offset + stride * rows as usize {
+        bail!("Pixel data is too short for {}x{}", width, rows);
+    }
+    let mut pixels = Vec::with_capacity((width * rows) as usize);
+    for y in 0..rows as usize {
+        // Lines are stored bottom up unless the height is negative
+        let row = if height > 0 { rows as usize - 1 - y } else { y };
+        let line = &data[offset + row * stride..][..width as usize * bytes];
+        pixels.extend(line.chunks(bytes).map(|pixel| Color { red: pixel[2], green: pixel[1], blue: pixel[0] }));
+    }
+    Ok(Image { width, height: rows, pixels })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 24-bit BMP, stored bottom up
+    fn bmp(width: u32, height: u32, pixel: impl Fn(u32, u32) -> Color) -> Vec<u8> {
+        let stride = (width * 3).div_ceil(4) * 4;
+        let mut data = b"BM".to_vec();
+        data.extend_from_slice(&(54 + stride * height).to_le_bytes());
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        data.extend_from_slice(&54u32.to_le_bytes());
+        data.extend_from_slice(&40u32.to_le_bytes());
+        data.extend_from_slice(&width.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&24u16.to_le_bytes());
+        data.extend_from_slice(&[0; 24]);
+        for y in (0..height).rev() {
+            let mut line: Vec<u8> = (0..width)
+                .flat_map(|x| {
+                    let color = pixel(x, y);
+                    [color.blue, color.green, color.red]
+                })
+                .collect();
+            line.resize(stride as usize, 0);
+            data.extend_from_slice(&line);
+        }
+        data
+    }
+
+    #[test]
+    fn test_images_are_fitted_to_windows() {
+        // Red on the left half and a dark green on the right
+        let data = bmp(64, 64, |x, _| if x < 32 { Color::RED } else { Color { red: 0, green: 0x60, blue: 0 } });
+        let image = Image::decode(&data).unwrap();
+        // Only images too big for any board are shrunk when decoded
+        assert_eq!((image.width, image.height), (64, 64));
+        let fitted = image.fit(&WindowData { x: 96, y: 0, width: 32, height: 16 });
+        assert_eq!((fitted.x, fitted.y), (8, 0));
+        assert_eq!((fitted.picture.width, fitted.picture.height), (16, 16));
+        assert_eq!(fitted.picture.pixel(0, 15), &Color::RED);
+        assert_eq!(fitted.picture.pixel(15, 0), &Color::BLACK);
+
+        let png = crate::scoreboard::render::Frame::new(3, 2).to_png(1).unwrap();
+        assert_eq!(Image::decode(&png).unwrap().fit(&WindowData { x: 0, y: 0, width: 30, height: 30 }).picture.width, 30);
+        assert!(Image::decode(b"GIF89a").is_err());
+    }
+}
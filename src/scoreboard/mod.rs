@@ -1,78 +1,295 @@
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, broadcast};
 use anyhow::Result;
-use log::{info, error};
+use base64::Engine;
+use chrono::{DateTime, Local, NaiveDateTime};
+use log::{info, warn};
 
+use crate::archive::{self, ArchivedMatch, ArchivedTeam};
 use crate::config::Config;
-use crate::protocol::{
-    ScoreboardClient, Command, TimeCommand, DisplayCommand, 
-    ScoreboardLayout, Color, windows
-};
+use crate::fixtures::Fixture;
+use crate::league::{self, MatchResult};
+use crate::rules::RuleProfile;
+use crate::protocol::{Color, Command, ScoreboardLayout, TimeCommand, windows};
+use crate::scoreboard::display::window_contents;
+use crate::scoreboard::render::{Frame, render};
+use crate::teams::Team;
+
+pub mod board;
+pub mod celebrations;
+pub mod discipline;
+pub mod display;
+pub mod events;
+pub mod font;
+pub mod image;
+pub mod pitch;
+pub mod render;
+pub mod roster;
+pub mod sponsors;
+pub mod substitutions;
+pub mod writer;
+
+pub use board::{Board, BoardStatus};
+pub use discipline::{Card, Discipline};
+pub use events::{EventLog, ScoreEvent, TimelineEntry};
+pub use crate::rules::Side;
+pub use image::Image;
+pub use roster::{Player, Rosters};
+pub use sponsors::{LoadedPlaylist, Playlist, SponsorFiles, SponsorStatus};
+pub use substitutions::{Substitution, SubstitutionKind, SubstitutionStatus, Substitutions};
+pub use pitch::{Pitch, Pitches, PitchSummary};
+
+/// Most minutes the match clock can be set to, as the board shows two digits
+pub const MAX_TIMER_MINUTES: u8 = 99;
 
 /// High-level scoreboard controller
 #[derive(Clone)]
 pub struct ScoreboardController {
-    client: Arc<Mutex<Option<ScoreboardClient>>>,
-    layout: ScoreboardLayout,
+    boards: Arc<Vec<Board>>,
     state: Arc<Mutex<ScoreboardState>>,
     simulation_mode: bool,
     config: Config,
+    /// Rule profiles from the config, worked out once
+    profiles: Arc<Vec<RuleProfile>>,
+    /// Scores recorded through the profile's actions
+    events: Arc<Mutex<EventLog>>,
+    /// Matchday squads, to name scorers
+    rosters: Arc<Mutex<Rosters>>,
+    /// Substitutions and temporary replacements
+    substitutions: Arc<Mutex<Substitutions>>,
+    /// Sponsor items shown between play
+    sponsors: Arc<Mutex<Playlist>>,
+    /// Where the playlist and its log are saved, when they are
+    sponsor_files: Option<SponsorFiles>,
+    /// Board previews as PNG data URLs, sent whenever the display changes
+    previews: broadcast::Sender<String>,
+}
+
+/// Both teams' scores at a point in the match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PeriodScore {
+    pub home: u16,
+    pub away: u16,
+}
+
+/// A score's scorer, such as "TRY 11 SMITH", in place of the team's name
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct ScorerFlash {
+    pub team: Side,
+    pub text: String,
+    /// Seconds left on the board
+    pub seconds: u8,
+}
+
+/// Text put up on the board for a while in place of the scores
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct BoardMessage {
+    pub id: u32,
+    pub text: String,
+    /// Seconds left on the board, counting down once it is shown
+    pub seconds: u16,
+    /// Window it replaces, or every window when none
+    pub window: Option<u8>,
+    /// Display effect from [`crate::protocol::effects`]
+    pub effect: u8,
+    pub color: Color,
+    /// Image shown in place of the text, fitted to the window
+    #[serde(skip)]
+    pub image: Option<Arc<Image>>,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ScoreboardState {
     pub home_team: String,
     pub away_team: String,
+    /// Names used when the full name doesn't fit a window
+    pub home_short_name: Option<String>,
+    pub away_short_name: Option<String>,
+    /// Colours of the team name windows
+    pub home_color: Color,
+    pub away_color: Color,
+    /// Registry IDs of teams picked with `select_teams`
+    pub home_team_id: Option<String>,
+    pub away_team_id: Option<String>,
     pub home_score: u16,
     pub away_score: u16,
     pub timer_minutes: u8,
     pub timer_seconds: u8,
     pub timer_running: bool,
+    /// Seconds left for a conversion attempt, while it is being taken
+    pub shot_clock: Option<u16>,
+    /// Period being played, or just ended during a break, from 1
+    pub period: u8,
+    /// Whether the match is in a break between periods
+    pub interval: bool,
+    /// How long the current break has lasted
+    pub interval_seconds: u16,
+    /// Score when the half-time break began
+    pub half_time: Option<PeriodScore>,
+    /// Whether the board shows the "HT" label instead of the scores
+    pub half_time_label: bool,
+    /// Scorer shown in place of a team's name for a few seconds
+    pub scorer_flash: Option<ScorerFlash>,
+    /// Cards shown so far, in the order they were shown
+    pub cards: Vec<Card>,
+    /// Messages waiting to be shown, the first one on the board now
+    pub messages: Vec<BoardMessage>,
+    /// Frames of the celebration being played for a score, the first one
+    /// on the board now
+    pub celebration: Vec<BoardMessage>,
+    /// Sponsor's item on the board between play, when no message is
+    pub sponsor: Option<BoardMessage>,
+    pub competition: Option<String>,
+    /// Name of the rule profile the match is played under
+    pub profile: String,
+    /// Length of each period
+    pub period_minutes: u8,
+    pub kick_off: Option<NaiveDateTime>,
+    /// When the match clock was first started, cleared when it is reset
+    pub started_at: Option<DateTime<Local>>,
+    /// Whether the match has been finished
+    pub full_time: bool,
     pub connected: bool,
     pub simulation_mode: bool,
 }
 
+impl ScoreboardState {
+    /// When the match is taken to have been played, `now` if it hasn't
+    /// been started
+    fn played_at(&self, now: DateTime<Local>) -> NaiveDateTime {
+        archive::played_at(self.kick_off, self.started_at, now)
+    }
+
+    /// Part of the day the board is free for sponsors in, none during
+    /// play or while the clock is stopped mid-period
+    fn sponsor_phase(&self) -> Option<sponsors::Phase> {
+        if self.timer_running {
+            None
+        } else if self.full_time {
+            Some(sponsors::Phase::PostMatch)
+        } else if self.interval {
+            Some(sponsors::Phase::HalfTime)
+        } else if self.started_at.is_none() {
+            Some(sponsors::Phase::PreMatch)
+        } else {
+            None
+        }
+    }
+
+    fn show_home_team(&mut self, team: &Team) {
+        self.home_team = team.details.name.clone();
+        self.home_short_name = team.details.short_name.clone();
+        self.home_color = team.details.primary_color.clone();
+        self.home_team_id = Some(team.id.clone());
+    }
+
+    fn show_away_team(&mut self, team: &Team) {
+        self.away_team = team.details.name.clone();
+        self.away_short_name = team.details.short_name.clone();
+        self.away_color = team.details.primary_color.clone();
+        self.away_team_id = Some(team.id.clone());
+    }
+}
+
 impl Default for ScoreboardState {
     fn default() -> Self {
         Self {
             home_team: "HOME".to_string(),
             away_team: "AWAY".to_string(),
+            home_short_name: None,
+            away_short_name: None,
+            home_color: Color::WHITE,
+            away_color: Color::WHITE,
+            home_team_id: None,
+            away_team_id: None,
             home_score: 0,
             away_score: 0,
             timer_minutes: 0,
             timer_seconds: 0,
             timer_running: false,
+            shot_clock: None,
+            period: 1,
+            interval: false,
+            interval_seconds: 0,
+            half_time: None,
+            half_time_label: false,
+            scorer_flash: None,
+            cards: Vec::new(),
+            messages: Vec::new(),
+            celebration: Vec::new(),
+            sponsor: None,
+            competition: None,
+            profile: "union".to_string(),
+            period_minutes: 40,
+            kick_off: None,
+            started_at: None,
+            full_time: false,
             connected: false,
             simulation_mode: false,
         }
     }
 }
 
+/// The config's default rule profile. It is checked when the config is
+/// loaded, so the first built-in profile only stands in for configs made in
+/// code.
+fn default_profile<'a>(profiles: &'a [RuleProfile], config: &Config) -> &'a RuleProfile {
+    profiles.iter()
+        .find(|profile| profile.name == config.default_profile)
+        .unwrap_or(&profiles[0])
+}
+
 impl ScoreboardController {
-    /// Create a new scoreboard controller
-    pub fn new(address: String, card_id: u8, simulation_mode: bool, config: Config) -> Self {
-        let client = if simulation_mode {
-            None
-        } else {
-            Some(ScoreboardClient::new(address, card_id))
-        };
-        let layout = ScoreboardLayout::standard_224x32();
+    /// Create a new scoreboard controller driving every board in the config
+    pub fn new(simulation_mode: bool, config: Config) -> Self {
+        let boards = config.boards()
+            .into_iter()
+            .map(|board| Board::new(board, config.text_fit.clone(), simulation_mode))
+            .collect();
+        let profiles = config.profiles();
+        let profile = default_profile(&profiles, &config).clone();
+        let sponsors = Playlist::new(&config.sponsors).unwrap_or_else(|e| {
+            warn!("Ignoring the sponsor playlist: {}", e);
+            Playlist::default()
+        });
         
         Self {
-            client: Arc::new(Mutex::new(client)),
-            layout,
-            state: Arc::new(Mutex::new({
-                let mut state = ScoreboardState::default();
-                state.simulation_mode = simulation_mode;
-                state.connected = simulation_mode; // In simulation mode, always "connected"
-                state
+            boards: Arc::new(boards),
+            state: Arc::new(Mutex::new(ScoreboardState {
+                simulation_mode,
+                connected: simulation_mode, // In simulation mode, always "connected"
+                profile: profile.name,
+                period_minutes: profile.period_minutes,
+                ..ScoreboardState::default()
             })),
             simulation_mode,
             config,
+            profiles: Arc::new(profiles),
+            events: Arc::new(Mutex::new(EventLog::default())),
+            rosters: Arc::new(Mutex::new(Rosters::default())),
+            substitutions: Arc::new(Mutex::new(Substitutions::default())),
+            sponsors: Arc::new(Mutex::new(sponsors)),
+            sponsor_files: None,
+            previews: broadcast::channel(16).0,
         }
     }
 
-    /// Initialize the scoreboard display
+    /// Save the sponsor playlist and log to files, carrying on from those
+    /// saved before a restart
+    pub fn with_sponsor_files(mut self, files: SponsorFiles) -> Self {
+        let config = files.load_playlist().unwrap_or_else(|| self.config.sponsors.clone());
+        let mut sponsors = Playlist::new(&config).unwrap_or_else(|e| {
+            warn!("Ignoring the sponsor playlist: {}", e);
+            Playlist::default()
+        });
+        sponsors.restore_log(files.load_log());
+        self.sponsors = Arc::new(Mutex::new(sponsors));
+        self.sponsor_files = Some(files);
+        self
+    }
+
+    /// Initialize the scoreboard display. Boards connect in the
+    /// background; their progress shows up in the board statuses.
     pub async fn initialize(&self) -> Result<()> {
         if self.simulation_mode {
             info!("Initializing scoreboard display in simulation mode...");
@@ -84,35 +301,24 @@ impl ScoreboardController {
             return Ok(());
         }
         
-        info!("Initializing scoreboard display...");
-        
-        let mut client_option = self.client.lock().await;
-        let client = client_option.as_mut()
-            .ok_or_else(|| anyhow::anyhow!("No client available in non-simulation mode"))?;
-        
-        // Connect to scoreboard
-        client.connect().await?;
-        
-        // Create windows
-        let windows = self.layout.all_windows();
-        let create_cmd = Command::DisplayMessage(DisplayCommand::CreateWindows(windows));
-        client.send_command(create_cmd).await?;
+        info!("Initializing {} board(s)...", self.boards.len());
         
-        // Update connection status
-        {
-            let mut state = self.state.lock().await;
-            state.connected = true;
+        let state = self.state.lock().await.clone();
+        for board in self.boards.iter() {
+            board.initialize(&state);
         }
-        
-        // Display initial content
-        self.update_display().await?;
-        
-        info!("Scoreboard initialized successfully");
         Ok(())
     }
 
-    /// Update the entire display
+    /// Update the entire display on every board, cutting short any
+    /// celebration being played
     pub async fn update_display(&self) -> Result<()> {
+        self.state.lock().await.celebration.clear();
+        self.refresh_display().await
+    }
+
+    /// Update the entire display on every board
+    async fn refresh_display(&self) -> Result<()> {
         if self.simulation_mode {
             // In simulation mode, just log the state
             let state = self.state.lock().await;
@@ -122,53 +328,636 @@ impl ScoreboardController {
                 state.timer_minutes, state.timer_seconds,
                 if state.timer_running { "(Running)" } else { "(Stopped)" }
             );
-            return Ok(());
+        } else {
+            self.show_state().await;
         }
         
-        let state = self.state.lock().await.clone();
-        
-        let mut client_option = self.client.lock().await;
-        let client = client_option.as_mut()
-            .ok_or_else(|| anyhow::anyhow!("No client available in non-simulation mode"))?;
-        
-        // Update team names
-        self.send_text_command(client, windows::HOME_NAME, &state.home_team, Color::WHITE).await?;
-        self.send_text_command(client, windows::AWAY_NAME, &state.away_team, Color::WHITE).await?;
-        
-        // Update scores
-        self.send_text_command(client, windows::HOME_SCORE, &state.home_score.to_string(), Color::GREEN).await?;
-        self.send_text_command(client, windows::AWAY_SCORE, &state.away_score.to_string(), Color::GREEN).await?;
-        
-        // Update timer display
-        let timer_text = format!("{:02}:{:02}", state.timer_minutes, state.timer_seconds);
-        let timer_color = if state.timer_running { Color::RED } else { Color::WHITE };
-        self.send_text_command(client, windows::TIMER, &timer_text, timer_color).await?;
-        
+        self.publish_preview().await;
         Ok(())
     }
 
-    /// Helper method to send text to a window
-    async fn send_text_command(&self, client: &mut ScoreboardClient, window_id: u8, text: &str, color: Color) -> Result<()> {
-        let cmd = Command::DisplayMessage(DisplayCommand::SendPureText {
-            window_id,
-            text: text.to_string(),
-            color,
-        });
-        client.send_command(cmd).await?;
-        Ok(())
+    /// Queue the current state for every board
+    async fn show_state(&self) {
+        let state = self.state.lock().await.clone();
+        for board in self.boards.iter() {
+            board.update_display(&state);
+        }
+    }
+
+    /// Send a fresh preview to anyone watching the board
+    async fn publish_preview(&self) {
+        if self.previews.receiver_count() == 0 {
+            return;
+        }
+        match self.preview_data_url().await {
+            Ok(url) => {
+                let _ = self.previews.send(url);
+            }
+            Err(e) => warn!("Failed to render preview: {}", e),
+        }
+    }
+
+    /// Current preview as a PNG data URL
+    pub async fn preview_data_url(&self) -> Result<String> {
+        let png = self.preview().await.to_png(1)?;
+        Ok(format!("data:image/png;base64,{}", base64::engine::general_purpose::STANDARD.encode(png)))
+    }
+
+    /// Receive a preview every time the display changes
+    pub fn subscribe_previews(&self) -> broadcast::Receiver<String> {
+        self.previews.subscribe()
+    }
+
+    /// Send a command to every board
+    fn send_command(&self, command: Command) {
+        for board in self.boards.iter() {
+            board.send_command(command.clone());
+        }
     }
 
     /// Set team names
     pub async fn set_teams(&self, home_team: String, away_team: String) -> Result<()> {
+        self.set_teams_with_short_names(home_team, None, away_team, None).await
+    }
+
+    /// Set team names along with the short names used when they don't fit
+    pub async fn set_teams_with_short_names(
+        &self,
+        home_team: String,
+        home_short_name: Option<String>,
+        away_team: String,
+        away_short_name: Option<String>,
+    ) -> Result<()> {
         {
             let mut state = self.state.lock().await;
             state.home_team = home_team;
+            state.home_short_name = home_short_name;
+            state.home_color = Color::WHITE;
+            state.home_team_id = None;
             state.away_team = away_team;
+            state.away_short_name = away_short_name;
+            state.away_color = Color::WHITE;
+            state.away_team_id = None;
         }
         
         self.update_display().await
     }
 
+    /// Show two teams from the registry, with each name in the team's colour
+    pub async fn select_teams(&self, home: &Team, away: &Team) -> Result<()> {
+        {
+            let mut state = self.state.lock().await;
+            state.show_home_team(home);
+            state.show_away_team(away);
+        }
+
+        self.update_display().await
+    }
+
+    /// Set up a new match from a fixture: teams (from the registry when
+    /// known), the competition's rule profile and period length, and
+    /// kick-off. Scores and the clock are reset.
+    pub async fn setup_fixture(&self, fixture: &Fixture, home: Option<&Team>, away: Option<&Team>) -> Result<()> {
+        {
+            let mut state = self.state.lock().await;
+            match home {
+                Some(team) => state.show_home_team(team),
+                None => {
+                    state.home_team = fixture.home.clone();
+                    state.home_short_name = None;
+                    state.home_color = Color::WHITE;
+                    state.home_team_id = None;
+                }
+            }
+            match away {
+                Some(team) => state.show_away_team(team),
+                None => {
+                    state.away_team = fixture.away.clone();
+                    state.away_short_name = None;
+                    state.away_color = Color::WHITE;
+                    state.away_team_id = None;
+                }
+            }
+
+            let competition = fixture.competition.as_deref().and_then(|name| self.config.competition(name));
+            let profile = self.rule_profile(competition.and_then(|competition| competition.profile.as_deref()));
+            state.competition = fixture.competition.clone();
+            state.period_minutes = fixture.period_minutes
+                .or(competition.and_then(|competition| competition.period_minutes))
+                .unwrap_or(profile.period_minutes);
+            state.profile = profile.name.clone();
+            state.kick_off = Some(fixture.kick_off);
+            state.home_score = 0;
+            state.away_score = 0;
+            state.shot_clock = None;
+            state.scorer_flash = None;
+            state.cards.clear();
+        }
+        self.events.lock().await.clear();
+        self.rosters.lock().await.clear();
+        self.substitutions.lock().await.clear();
+
+        self.reset_timer().await
+    }
+
+    /// Rule profile the match is played under, with its competition's
+    /// points
+    pub async fn profile(&self) -> RuleProfile {
+        let state = self.state.lock().await;
+        let mut profile = self.rule_profile(Some(&state.profile)).clone();
+        if let Some(competition) = state.competition.as_deref().and_then(|name| self.config.competition(name)) {
+            competition.apply(&mut profile);
+        }
+        profile
+    }
+
+    /// Every rule profile a match can be played under
+    pub fn profiles(&self) -> &[RuleProfile] {
+        &self.profiles
+    }
+
+    /// A rule profile by name, or the default profile when none is given
+    /// or there is none of that name
+    fn rule_profile(&self, name: Option<&str>) -> &RuleProfile {
+        name.and_then(|name| self.profiles.iter().find(|profile| profile.name == name))
+            .unwrap_or_else(|| default_profile(&self.profiles, &self.config))
+    }
+
+    /// Switch to another rule profile, taking its period length
+    pub async fn set_profile(&self, name: &str) -> Result<()> {
+        let profile = self.profiles.iter()
+            .find(|profile| profile.name == name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown rule profile: {}", name))?;
+        {
+            let mut state = self.state.lock().await;
+            state.profile = profile.name.clone();
+            state.period_minutes = profile.period_minutes;
+        }
+
+        self.update_display().await
+    }
+
+    /// Score one of the active profile's actions and record it
+    pub async fn score_action(&self, team: &str, action: &str) -> Result<ScoreEvent> {
+        self.score_action_by(team, action, None).await
+    }
+
+    /// Score one of the active profile's actions, naming the scorer by
+    /// shirt number, and record it
+    pub async fn score_action_by(&self, team: &str, action: &str, player: Option<u8>) -> Result<ScoreEvent> {
+        let side: Side = team.parse()?;
+        let profile = self.profile().await;
+        let action = profile.action(action)
+            .ok_or_else(|| anyhow::anyhow!("No action '{}' in {}", action, profile.label))?;
+        let player = match player {
+            Some(number) => Some(self.rosters.lock().await.player(side, number)?),
+            None => None,
+        };
+
+        let event = {
+            let mut state = self.state.lock().await;
+            match side {
+                Side::Home => state.home_score = state.home_score.saturating_add(action.points),
+                Side::Away => state.away_score = state.away_score.saturating_add(action.points),
+            }
+            if action.converted && self.config.shot_clock.enabled {
+                state.shot_clock = Some(self.config.shot_clock.seconds);
+            } else if action.conversion {
+                state.shot_clock = None;
+            }
+            state.celebration = self.celebration(&state, side, &action.name);
+            if let Some(player) = &player
+                && self.config.scorer_flash.enabled
+            {
+                let text = format!("{} {} {}", action.label, player.number, player.surname());
+                state.scorer_flash = Some(ScorerFlash {
+                    team: side,
+                    text: text.trim_end().to_uppercase(),
+                    seconds: self.config.scorer_flash.seconds,
+                });
+            }
+            self.events.lock().await.record(side, action, state.timer_minutes, state.timer_seconds, player)
+        };
+
+        self.refresh_display().await?;
+        Ok(event)
+    }
+
+    /// Frames to play for a team scoring an action, for callers already
+    /// holding the state
+    fn celebration(&self, state: &ScoreboardState, side: Side, action: &str) -> Vec<BoardMessage> {
+        let Some(celebration) = celebrations::find(&self.config.celebrations, action, side) else {
+            return Vec::new();
+        };
+        let team = match side {
+            Side::Home => &state.home_team,
+            Side::Away => &state.away_team,
+        };
+        let frames = celebrations::frames(celebration, team).and_then(|frames| {
+            frames.iter().try_for_each(|frame| self.check_window(frame.window))?;
+            Ok(frames)
+        });
+        frames.unwrap_or_else(|e| {
+            warn!("Skipping the {} celebration: {}", action, e);
+            Vec::new()
+        })
+    }
+
+    /// Make sure every board has a window a message is aimed at, as one
+    /// no board can show would hold up the queue
+    fn check_window(&self, window: Option<u8>) -> Result<()> {
+        let Some(window) = window else {
+            return Ok(());
+        };
+        match self.boards.iter().find(|board| board.layout().window(window).is_none()) {
+            Some(board) => anyhow::bail!(
+                "Board {} has no {} window",
+                board.name(),
+                windows::name(window).unwrap_or("such"),
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Take back a team's latest score of an action, returning the points
+    /// removed. Scores entered by hand have no record, so the action's
+    /// points are taken off when none is found.
+    pub async fn undo_action(&self, team: &str, action: &str) -> Result<u16> {
+        let side: Side = team.parse()?;
+        // Scores set by hand have no event, and are corrected with set_score
+        let (removed, latest) = {
+            let mut events = self.events.lock().await;
+            let latest = events.events().last().map(|event| event.id);
+            (events.remove_last(side, action), latest)
+        };
+        let removed = removed
+            .ok_or_else(|| anyhow::anyhow!("No {} recorded for {} to take back", action, side.as_str()))?;
+
+        // An earlier try isn't the one a running shot clock is for
+        if Some(removed.id) == latest {
+            self.cancel_shot_clock_after(action).await;
+        }
+        let points = removed.points;
+        self.take_points(side, points).await?;
+        Ok(points)
+    }
+
+    /// Take back the latest recorded score
+    pub async fn undo_last(&self) -> Result<ScoreEvent> {
+        let event = self.events.lock().await.pop()
+            .ok_or_else(|| anyhow::anyhow!("No scores to undo"))?;
+        self.cancel_shot_clock_after(&event.action).await;
+        self.take_points(event.team, event.points).await?;
+        Ok(event)
+    }
+
+    /// Start the conversion shot clock
+    pub async fn start_shot_clock(&self) -> Result<()> {
+        self.state.lock().await.shot_clock = Some(self.config.shot_clock.seconds);
+        self.update_display().await
+    }
+
+    /// Stop the shot clock, such as when the conversion was missed or not
+    /// taken
+    pub async fn cancel_shot_clock(&self) -> Result<()> {
+        self.state.lock().await.shot_clock = None;
+        self.update_display().await
+    }
+
+    /// Scores recorded so far
+    pub async fn events(&self) -> Vec<ScoreEvent> {
+        self.events.lock().await.events().to_vec()
+    }
+
+    /// Scores and substitutions so far, in match clock order
+    pub async fn timeline(&self) -> Vec<TimelineEntry> {
+        let substitutions = self.substitutions().await;
+        events::timeline(self.events.lock().await.events(), &substitutions)
+    }
+
+    /// The match's result as it stands, with tries counted from the
+    /// recorded scores
+    pub async fn result(&self) -> MatchResult {
+        let state = self.state.lock().await.clone();
+        let events = self.events.lock().await;
+        let tries = |side: Side| league::tries(events.events(), side);
+        let played_at = state.played_at(Local::now());
+        MatchResult {
+            id: MatchResult::id(&state.home_team, &state.away_team, played_at),
+            competition: state.competition,
+            home_tries: tries(Side::Home),
+            away_tries: tries(Side::Away),
+            home: state.home_team,
+            away: state.away_team,
+            home_score: state.home_score,
+            away_score: state.away_score,
+            played_at,
+        }
+    }
+
+    /// Stop the clock and take a record of the match, to be archived. The
+    /// board keeps showing the final score.
+    pub async fn finish_match(&self, pitch: &str) -> ArchivedMatch {
+        if let Err(e) = self.stop_timer().await {
+            warn!("Failed to stop the clock at full time: {}", e);
+        }
+        self.state.lock().await.full_time = true;
+        self.snapshot(pitch).await
+    }
+
+    /// A record of the match as it stands
+    pub async fn snapshot(&self, pitch: &str) -> ArchivedMatch {
+        let state = self.state.lock().await.clone();
+        let events = self.events().await;
+        let rosters = self.rosters().await;
+        let substitutions = self.substitutions().await;
+        let finished_at = Local::now();
+        let played_at = state.played_at(finished_at);
+        ArchivedMatch {
+            id: MatchResult::id(&state.home_team, &state.away_team, played_at),
+            pitch: pitch.to_string(),
+            competition: state.competition,
+            profile: state.profile,
+            period_minutes: state.period_minutes,
+            home: ArchivedTeam {
+                name: state.home_team,
+                short_name: state.home_short_name,
+                team_id: state.home_team_id,
+                color: state.home_color,
+                score: state.home_score,
+                roster: rosters.home,
+            },
+            away: ArchivedTeam {
+                name: state.away_team,
+                short_name: state.away_short_name,
+                team_id: state.away_team_id,
+                color: state.away_color,
+                score: state.away_score,
+                roster: rosters.away,
+            },
+            half_time: state.half_time,
+            minutes: state.timer_minutes,
+            seconds: state.timer_seconds,
+            kick_off: state.kick_off,
+            started_at: state.started_at,
+            finished_at,
+            events,
+            substitutions,
+            cards: state.cards,
+        }
+    }
+
+    /// Both teams' squads
+    pub async fn rosters(&self) -> Rosters {
+        self.rosters.lock().await.clone()
+    }
+
+    /// Replace a team's squad
+    pub async fn set_roster(&self, team: &str, players: Vec<Player>) -> Result<()> {
+        let side: Side = team.parse()?;
+        self.rosters.lock().await.set(side, players)
+    }
+
+    /// Show a player a card: one of the profile's sin bins, `red`, or
+    /// `red_20` when the profile allows the player to be replaced after a
+    /// while
+    pub async fn give_card(&self, team: &str, card: &str, player: u8) -> Result<Card> {
+        let side: Side = team.parse()?;
+        let profile = self.profile().await;
+        let remaining = match card {
+            discipline::RED => None,
+            discipline::RED_20 => {
+                let minutes = profile.red_replacement_minutes
+                    .ok_or_else(|| anyhow::anyhow!("{} has no 20-minute red cards", profile.label))?;
+                Some(minutes as u16 * 60)
+            }
+            _ => {
+                let sin_bin = profile.sin_bin(card)
+                    .ok_or_else(|| anyhow::anyhow!("No '{}' card in {}", card, profile.label))?;
+                Some(sin_bin.minutes as u16 * 60)
+            }
+        };
+        let player = self.rosters.lock().await.player(side, player)?;
+
+        let card = {
+            let mut state = self.state.lock().await;
+            let card = Card {
+                id: state.cards.iter().map(|card| card.id).max().unwrap_or(0) + 1,
+                team: side,
+                card: card.to_string(),
+                player,
+                minute: state.timer_minutes,
+                second: state.timer_seconds,
+                remaining,
+                recorded_at: Local::now(),
+            };
+            state.cards.push(card.clone());
+            card
+        };
+        info!("{} card at {:02}:{:02} for {} number {}", card.card, card.minute, card.second, team, card.player.number);
+
+        self.update_display().await?;
+        Ok(card)
+    }
+
+    /// Queue a message to replace a window, or the whole board, for a
+    /// number of seconds. The scores come back once the queue is empty.
+    pub async fn queue_message(&self, text: String, seconds: u16, window: Option<u8>, effect: u8, color: Color) -> Result<BoardMessage> {
+        if text.trim().is_empty() {
+            anyhow::bail!("The message has no text");
+        }
+        self.push_message(BoardMessage { id: 0, text, seconds, window, effect, color, image: None }).await
+    }
+
+    /// Queue an image to replace a window, or the whole board, for a number
+    /// of seconds, fitted to the window on each board. `name` stands for it
+    /// in the queue.
+    pub async fn queue_image(&self, name: String, image: Image, seconds: u16, window: Option<u8>, effect: u8) -> Result<BoardMessage> {
+        let message = BoardMessage { id: 0, text: name, seconds, window, effect, color: Color::WHITE, image: Some(Arc::new(image)) };
+        self.push_message(message).await
+    }
+
+    /// Add a message to the end of the queue with the next ID
+    async fn push_message(&self, message: BoardMessage) -> Result<BoardMessage> {
+        if message.seconds == 0 {
+            anyhow::bail!("The message has to be shown for at least a second");
+        }
+        self.check_window(message.window)?;
+        let message = {
+            let mut state = self.state.lock().await;
+            let message = BoardMessage {
+                id: state.messages.iter().map(|message| message.id).max().unwrap_or(0) + 1,
+                ..message
+            };
+            state.messages.push(message.clone());
+            message
+        };
+
+        self.update_display().await?;
+        Ok(message)
+    }
+
+    /// Messages waiting to be shown, the first one on the board now
+    pub async fn messages(&self) -> Vec<BoardMessage> {
+        self.state.lock().await.messages.clone()
+    }
+
+    /// Take a message off the board or out of the queue
+    pub async fn cancel_message(&self, id: u32) -> Result<BoardMessage> {
+        let message = {
+            let mut state = self.state.lock().await;
+            let index = state.messages.iter()
+                .position(|message| message.id == id)
+                .ok_or_else(|| anyhow::anyhow!("No message {}", id))?;
+            state.messages.remove(index)
+        };
+        self.update_display().await?;
+        Ok(message)
+    }
+
+    /// The sponsor playlist and what it is showing
+    pub async fn sponsors(&self) -> SponsorStatus {
+        self.sponsors.lock().await.status()
+    }
+
+    /// Replace the sponsor playlist
+    pub async fn set_sponsors(&self, config: crate::config::SponsorConfig) -> Result<SponsorStatus> {
+        // Logos are read from files, before the board is held up for them
+        let playlist = config.clone();
+        let loaded = tokio::task::spawn_blocking(move || LoadedPlaylist::load(playlist)).await??;
+        {
+            let mut state = self.state.lock().await;
+            self.sponsors.lock().await.set(loaded);
+            state.sponsor = None;
+        }
+        if let Some(files) = &self.sponsor_files {
+            files.save_playlist(&config).await?;
+        }
+        self.save_sponsor_log().await;
+        self.update_display().await?;
+        Ok(self.sponsors().await)
+    }
+
+    /// Append the showings logged since the last save to the log file
+    async fn save_sponsor_log(&self) {
+        let showings = self.sponsors.lock().await.take_unsaved();
+        let Some(files) = self.sponsor_files.as_ref().filter(|_| !showings.is_empty()) else {
+            return;
+        };
+        if let Err(e) = files.append_log(&showings).await {
+            warn!("Failed to save the sponsor log: {}", e);
+        }
+    }
+
+    /// Every sponsor item shown so far
+    pub async fn sponsor_log(&self) -> Vec<sponsors::Showing> {
+        self.sponsors.lock().await.log().to_vec()
+    }
+
+    /// Take back a card shown by mistake
+    pub async fn remove_card(&self, id: u32) -> Result<Card> {
+        let card = {
+            let mut state = self.state.lock().await;
+            let index = state.cards.iter()
+                .position(|card| card.id == id)
+                .ok_or_else(|| anyhow::anyhow!("No card {}", id))?;
+            state.cards.remove(index)
+        };
+        self.update_display().await?;
+        Ok(card)
+    }
+
+    /// Cards shown so far, with each team's players on the field
+    pub async fn discipline(&self) -> Discipline {
+        let players = self.profile().await.players;
+        let cards = self.state.lock().await.cards.clone();
+        Discipline {
+            home_players: discipline::players_on_field(&cards, Side::Home, players),
+            away_players: discipline::players_on_field(&cards, Side::Away, players),
+            cards,
+        }
+    }
+
+    /// Replace a player, by shirt numbers. HIA and blood replacements are
+    /// temporary, with a countdown on the match clock for the player to
+    /// return.
+    pub async fn substitute(&self, team: &str, kind: SubstitutionKind, off: u8, on: u8) -> Result<Substitution> {
+        let side: Side = team.parse()?;
+        let (off, on) = {
+            let rosters = self.rosters.lock().await;
+            (rosters.player(side, off)?, rosters.player(side, on)?)
+        };
+        let players = self.profile().await.players;
+        let sent_off = self.state.lock().await.cards.iter()
+            .any(|card| card.team == side && card.player.number == off.number && card.is_off());
+        if sent_off {
+            anyhow::bail!("Number {} is off the field for a card", off.number);
+        }
+        let minutes = match kind {
+            SubstitutionKind::Hia => self.config.replacements.hia_minutes,
+            SubstitutionKind::Blood => self.config.replacements.blood_minutes,
+            SubstitutionKind::Permanent => 0,
+        };
+        let (minute, second) = self.clock().await;
+        let substitution = {
+            let mut substitutions = self.substitutions.lock().await;
+            let on_field = substitutions.on_field(side, players);
+            if !on_field.contains(&off.number) {
+                anyhow::bail!("Number {} isn't on the field", off.number);
+            }
+            if on_field.contains(&on.number) {
+                anyhow::bail!("Number {} is already on the field", on.number);
+            }
+            substitutions.record(side, kind, off, on, (minute, second), minutes as u16 * 60)?
+        };
+        info!("{} at {:02}:{:02}: {} number {} off, {} on", kind.label(), minute, second, team, substitution.off.number, substitution.on.number);
+        Ok(substitution)
+    }
+
+    /// End a temporary replacement, with the player returning or the
+    /// replacement staying on for good
+    pub async fn end_replacement(&self, id: u32, returned: bool) -> Result<Substitution> {
+        let (minute, second) = self.clock().await;
+        let substitution = self.substitutions.lock().await.end(id, returned, (minute, second))?;
+        info!("{} replacement {} ended at {:02}:{:02}: {:?}", substitution.kind.label(), id, minute, second, substitution.status);
+        Ok(substitution)
+    }
+
+    /// Take back a substitution recorded by mistake
+    pub async fn remove_substitution(&self, id: u32) -> Result<Substitution> {
+        self.substitutions.lock().await.remove(id)
+    }
+
+    /// Substitutions made so far
+    pub async fn substitutions(&self) -> Vec<Substitution> {
+        self.substitutions.lock().await.substitutions().to_vec()
+    }
+
+    async fn clock(&self) -> (u8, u8) {
+        let state = self.state.lock().await;
+        (state.timer_minutes, state.timer_seconds)
+    }
+
+    /// Stop the shot clock when the latest score, the only one it can be
+    /// running for, is taken back
+    async fn cancel_shot_clock_after(&self, action: &str) {
+        if self.profile().await.action(action).is_some_and(|action| action.converted) {
+            self.state.lock().await.shot_clock = None;
+        }
+    }
+
+    async fn take_points(&self, side: Side, points: u16) -> Result<()> {
+        {
+            let mut state = self.state.lock().await;
+            match side {
+                Side::Home => state.home_score = state.home_score.saturating_sub(points),
+                Side::Away => state.away_score = state.away_score.saturating_sub(points),
+            }
+        }
+
+        self.update_display().await
+    }
+
     /// Set scores
     pub async fn set_scores(&self, home_score: u16, away_score: u16) -> Result<()> {
         {
@@ -202,11 +991,28 @@ impl ScoreboardController {
 
     /// Reset scores
     pub async fn reset_scores(&self) -> Result<()> {
-        self.set_scores(0, 0).await
+        {
+            let mut state = self.state.lock().await;
+            self.events.lock().await.clear();
+            state.home_score = 0;
+            state.away_score = 0;
+            // Nothing is left for these to be shown for
+            state.shot_clock = None;
+            state.scorer_flash = None;
+            state.celebration.clear();
+        }
+
+        self.update_display().await
     }
 
     /// Set timer
     pub async fn set_timer(&self, minutes: u8, seconds: u8) -> Result<()> {
+        if seconds >= 60 {
+            anyhow::bail!("Seconds have to be less than 60, not {}", seconds);
+        }
+        if minutes > MAX_TIMER_MINUTES {
+            anyhow::bail!("The clock only goes up to {} minutes", MAX_TIMER_MINUTES);
+        }
         {
             let mut state = self.state.lock().await;
             state.timer_minutes = minutes;
@@ -214,16 +1020,13 @@ impl ScoreboardController {
         }
         
         if !self.simulation_mode {
-            // Send time command to scoreboard
-            let mut client_option = self.client.lock().await;
-            let client = client_option.as_mut()
-                .ok_or_else(|| anyhow::anyhow!("No client available in non-simulation mode"))?;
+            // Send time command to the boards
             let cmd = Command::TimeControl(TimeCommand::Set {
                 hours: 0,
                 minutes,
                 seconds,
             });
-            client.send_command(cmd).await?;
+            self.send_command(cmd);
         }
         
         self.update_display().await
@@ -234,14 +1037,15 @@ impl ScoreboardController {
         {
             let mut state = self.state.lock().await;
             state.timer_running = true;
+            state.started_at.get_or_insert_with(Local::now);
+            state.full_time = false;
+            state.sponsor = None;
+            self.sponsors.lock().await.pause();
         }
         
         if !self.simulation_mode {
-            let mut client_option = self.client.lock().await;
-            let client = client_option.as_mut()
-                .ok_or_else(|| anyhow::anyhow!("No client available in non-simulation mode"))?;
             let cmd = Command::TimeControl(TimeCommand::StartStop(true));
-            client.send_command(cmd).await?;
+            self.send_command(cmd);
         }
         
         self.update_display().await
@@ -255,96 +1059,182 @@ impl ScoreboardController {
         }
         
         if !self.simulation_mode {
-            let mut client_option = self.client.lock().await;
-            let client = client_option.as_mut()
-                .ok_or_else(|| anyhow::anyhow!("No client available in non-simulation mode"))?;
             let cmd = Command::TimeControl(TimeCommand::StartStop(false));
-            client.send_command(cmd).await?;
+            self.send_command(cmd);
         }
         
         self.update_display().await
     }
 
-    /// Reset timer
-    pub async fn reset_timer(&self) -> Result<()> {
-        self.set_timer(0, 0).await?;
-        self.stop_timer().await
-    }
-
-    // Rugby scoring methods
-
-    /// Add a try to the specified team (5 points)
-    pub async fn add_try(&self, team: &str) -> Result<()> {
-        let try_points = self.config.rugby.try_points;
-        match team.to_lowercase().as_str() {
-            "home" => {
-                let current_score = self.get_home_score().await;
-                self.set_scores(current_score + try_points, self.get_away_score().await).await
+    /// Advance the running match clock, with the temporary replacements'
+    /// countdowns, the shot clock, the celebration or message on the board
+    /// and the sponsor playlist by one second
+    pub async fn tick(&self) {
+        let (running, served, clock) = {
+            let mut state = self.state.lock().await;
+            let mut sponsors = self.sponsors.lock().await;
+            // Messages put up by hand and celebrations hold the playlist back
+            let phase = state.sponsor_phase().filter(|_| state.messages.is_empty() && state.celebration.is_empty());
+            if !state.timer_running && state.shot_clock.is_none() && !state.interval && state.scorer_flash.is_none()
+                && state.messages.is_empty() && state.celebration.is_empty() && state.sponsor.is_none()
+                && !sponsors.plays_in(phase)
+            {
+                return;
             }
-            "away" => {
-                let current_score = self.get_away_score().await;
-                self.set_scores(self.get_home_score().await, current_score + try_points).await
+            if state.interval {
+                state.interval_seconds = state.interval_seconds.saturating_add(1);
+                let half_time = &self.config.half_time;
+                state.half_time_label = half_time.alternate
+                    && state.half_time.is_some()
+                    && state.period == self.half_time_period(&state)
+                    && (state.interval_seconds / half_time.seconds.max(1) as u16) % 2 == 1;
             }
-            _ => Err(anyhow::anyhow!("Invalid team: {}", team)),
-        }
-    }
-
-    /// Remove a try from the specified team (subtract 5 points)
-    pub async fn remove_try(&self, team: &str) -> Result<()> {
-        let try_points = self.config.rugby.try_points;
-        match team.to_lowercase().as_str() {
-            "home" => {
-                let current_score = self.get_home_score().await;
-                let new_score = if current_score >= try_points {
-                    current_score - try_points
-                } else {
-                    0
-                };
-                self.set_scores(new_score, self.get_away_score().await).await
+            if state.timer_running {
+                let elapsed = state.timer_minutes as u16 * 60 + state.timer_seconds as u16 + 1;
+                state.timer_minutes = (elapsed / 60).min(u8::MAX as u16) as u8;
+                state.timer_seconds = (elapsed % 60) as u8;
             }
-            "away" => {
-                let current_score = self.get_away_score().await;
-                let new_score = if current_score >= try_points {
-                    current_score - try_points
-                } else {
-                    0
-                };
-                self.set_scores(self.get_home_score().await, new_score).await
+            let served = if state.timer_running { discipline::tick(&mut state.cards) } else { Vec::new() };
+            // The shot clock shows 0 for a second before it disappears
+            state.shot_clock = state.shot_clock.and_then(|seconds| seconds.checked_sub(1));
+            if let Some(flash) = state.scorer_flash.as_mut() {
+                flash.seconds = flash.seconds.saturating_sub(1);
             }
-            _ => Err(anyhow::anyhow!("Invalid team: {}", team)),
+            if state.scorer_flash.as_ref().is_some_and(|flash| flash.seconds == 0) {
+                state.scorer_flash = None;
+            }
+            // Messages wait behind a celebration being played
+            if let Some(frame) = state.celebration.first_mut() {
+                frame.seconds = frame.seconds.saturating_sub(1);
+                if frame.seconds == 0 {
+                    state.celebration.remove(0);
+                }
+            } else if let Some(message) = state.messages.first_mut() {
+                message.seconds = message.seconds.saturating_sub(1);
+                if message.seconds == 0 {
+                    let message = state.messages.remove(0);
+                    info!("Message {} '{}' taken off the board", message.id, message.text);
+                }
+            }
+            state.sponsor = sponsors.tick(phase);
+            (state.timer_running, served, (state.timer_minutes, state.timer_seconds))
+        };
+        for card in served {
+            let time_up = if card.card == discipline::RED_20 { "may be replaced" } else { "may return" };
+            info!("{} card {}: {} number {} {}", card.card, card.id, card.team.as_str(), card.player.number, time_up);
         }
+        self.save_sponsor_log().await;
+        if running {
+            for substitution in self.substitutions.lock().await.tick(clock) {
+                warn!("{} replacement {}: time is up for number {} to return", substitution.kind.label(), substitution.id, substitution.off.number);
+            }
+        }
+        
+        if !self.simulation_mode {
+            self.show_state().await;
+        }
+        self.publish_preview().await;
     }
 
-    /// Add a conversion to the specified team (2 points)
-    pub async fn add_conversion(&self, team: &str) -> Result<()> {
-        let conversion_points = self.config.rugby.conversion_points;
-        match team.to_lowercase().as_str() {
-            "home" => {
-                let current_score = self.get_home_score().await;
-                self.set_scores(current_score + conversion_points, self.get_away_score().await).await
+    /// End the current period: stop the clock and start the break,
+    /// keeping the score at half time
+    pub async fn end_period(&self) -> Result<ScoreboardState> {
+        {
+            let mut state = self.state.lock().await;
+            let periods = self.periods(&state);
+            if state.interval {
+                anyhow::bail!("Period {} has already ended", state.period);
+            }
+            if state.period >= periods {
+                anyhow::bail!("Period {} is the last one; finish the match instead", state.period);
             }
-            "away" => {
-                let current_score = self.get_away_score().await;
-                self.set_scores(self.get_home_score().await, current_score + conversion_points).await
+            state.interval = true;
+            state.interval_seconds = 0;
+            if state.period == self.half_time_period(&state) {
+                state.half_time = Some(PeriodScore { home: state.home_score, away: state.away_score });
             }
-            _ => Err(anyhow::anyhow!("Invalid team: {}", team)),
         }
+        self.stop_timer().await?;
+        Ok(self.get_state().await)
     }
 
-    /// Add a penalty to the specified team (3 points)
-    pub async fn add_penalty(&self, team: &str) -> Result<()> {
-        let penalty_points = self.config.rugby.penalty_points;
-        match team.to_lowercase().as_str() {
-            "home" => {
-                let current_score = self.get_home_score().await;
-                self.set_scores(current_score + penalty_points, self.get_away_score().await).await
-            }
-            "away" => {
-                let current_score = self.get_away_score().await;
-                self.set_scores(self.get_home_score().await, current_score + penalty_points).await
+    /// Start the next period after a break, with the clock carrying on
+    /// from the end of the previous one
+    pub async fn start_period(&self) -> Result<ScoreboardState> {
+        let minutes = {
+            let mut state = self.state.lock().await;
+            if !state.interval {
+                anyhow::bail!("Period {} hasn't ended yet", state.period);
             }
-            _ => Err(anyhow::anyhow!("Invalid team: {}", team)),
+            state.period += 1;
+            state.interval = false;
+            state.interval_seconds = 0;
+            state.half_time_label = false;
+            (state.period as u16 - 1) * state.period_minutes as u16
+        };
+        self.set_timer(minutes.min(MAX_TIMER_MINUTES as u16) as u8, 0).await?;
+        self.start_timer().await?;
+        Ok(self.get_state().await)
+    }
+
+    /// Number of periods in the active profile, for callers already
+    /// holding the state
+    fn periods(&self, state: &ScoreboardState) -> u8 {
+        self.rule_profile(Some(&state.profile)).periods
+    }
+
+    /// The period at the end of which it is half time
+    fn half_time_period(&self, state: &ScoreboardState) -> u8 {
+        (self.periods(state) / 2).max(1)
+    }
+
+    /// Reset timer
+    pub async fn reset_timer(&self) -> Result<()> {
+        {
+            let mut state = self.state.lock().await;
+            state.started_at = None;
+            state.full_time = false;
+            state.period = 1;
+            state.interval = false;
+            state.interval_seconds = 0;
+            state.half_time = None;
+            state.half_time_label = false;
         }
+        self.set_timer(0, 0).await?;
+        self.stop_timer().await
+    }
+
+    /// Point a board (the first one if `board` is `None`) at a different
+    /// control card and reinitialize it
+    pub async fn set_target(&self, board: Option<&str>, address: String, card_id: u8) -> Result<()> {
+        let target = match board {
+            Some(name) => self.boards.iter().find(|b| b.name() == name)
+                .ok_or_else(|| anyhow::anyhow!("Unknown board: {}", name))?,
+            None => self.boards.first()
+                .ok_or_else(|| anyhow::anyhow!("No boards configured"))?,
+        };
+
+        if self.simulation_mode {
+            info!("Simulation mode - new target {} (card {}) for board '{}' will be used on restart", address, card_id, target.name());
+            return Ok(());
+        }
+
+        target.set_target(address, card_id);
+        Ok(())
+    }
+
+    /// Configuration the controller was created with
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Render what the first board shows for the current state
+    pub async fn preview(&self) -> Frame {
+        let layout = self.boards.first()
+            .map(|board| board.layout().clone())
+            .unwrap_or_else(ScoreboardLayout::standard_224x32);
+        let state = self.state.lock().await.clone();
+        render(&layout, &window_contents(&state, &layout, &self.config.text_fit))
     }
 
     /// Get current state
@@ -362,37 +1252,383 @@ impl ScoreboardController {
         self.state.lock().await.away_score
     }
 
-    /// Check connection status
+    /// Check connection status - true if any board is connected
     pub async fn is_connected(&self) -> bool {
         if self.simulation_mode {
             return self.state.lock().await.connected;
         }
         
-        let client_option = self.client.lock().await;
-        match client_option.as_ref() {
-            Some(client) => client.is_connected(),
-            None => false,
-        }
+        self.boards.iter().any(Board::is_connected)
+    }
+
+    /// Connection status of each board
+    pub async fn board_statuses(&self) -> Vec<BoardStatus> {
+        self.boards.iter().map(Board::status).collect()
     }
 
-    /// Ensure connection and update status
+    /// Ask any offline board to reconnect and report whether any board is
+    /// currently connected
     pub async fn ensure_connection(&self) -> Result<bool> {
         if self.simulation_mode {
             return Ok(self.state.lock().await.connected);
         }
         
-        let mut client_option = self.client.lock().await;
-        let client = client_option.as_mut()
-            .ok_or_else(|| anyhow::anyhow!("No client available in non-simulation mode"))?;
-            
-        let result = client.ensure_connection().await;
-        
-        let connected = result.is_ok() && client.is_connected();
-        {
-            let mut state = self.state.lock().await;
-            state.connected = connected;
+        for board in self.boards.iter() {
+            board.ensure_connection();
         }
-        
-        Ok(connected)
+        Ok(self.is_connected().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_display_updates_publish_previews() {
+        let controller = ScoreboardController::new(true, Config::default());
+        let mut previews = controller.subscribe_previews();
+
+        controller.score_action("home", "try").await.unwrap();
+        let preview = previews.try_recv().unwrap();
+        assert!(preview.starts_with("data:image/png;base64,"));
+    }
+
+    #[tokio::test]
+    async fn test_actions_follow_the_active_profile() {
+        let controller = ScoreboardController::new(true, Config::default());
+        controller.score_action("home", "try").await.unwrap();
+        assert_eq!(controller.get_home_score().await, 5);
+
+        controller.set_profile("league").await.unwrap();
+        assert_eq!(controller.get_state().await.period_minutes, 40);
+        controller.score_action("away", "try").await.unwrap();
+        assert_eq!(controller.score_action("away", "drop_goal").await.unwrap().points, 1);
+        assert_eq!(controller.get_away_score().await, 5);
+
+        controller.set_profile("football").await.unwrap();
+        assert!(controller.score_action("home", "try").await.is_err());
+        assert!(controller.set_profile("quidditch").await.is_err());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_shot_clock_runs_from_try_to_conversion() {
+        let mut config = Config::default();
+        config.shot_clock.enabled = true;
+        config.shot_clock.seconds = 2;
+        let controller = ScoreboardController::new(true, config);
+
+        controller.score_action("home", "try").await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, Some(2));
+        controller.score_action("home", "conversion").await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, None);
+
+        controller.score_action("away", "try").await.unwrap();
+        controller.tick().await;
+        controller.tick().await;
+        assert_eq!(controller.get_state().await.shot_clock, Some(0));
+        controller.tick().await;
+        assert_eq!(controller.get_state().await.shot_clock, None);
+
+        controller.score_action("away", "try").await.unwrap();
+        controller.undo_last().await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, None);
+
+        controller.score_action("away", "try").await.unwrap();
+        controller.cancel_shot_clock().await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, None);
+        // Taking back an earlier try leaves the clock running for the latest
+        controller.score_action("home", "try").await.unwrap();
+        controller.undo_action("away", "try").await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, Some(2));
+        controller.undo_action("home", "try").await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, None);
+        // Penalty tries come without a conversion
+        controller.score_action("home", "penalty_try").await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, None);
+
+        controller.score_action("away", "try").await.unwrap();
+        controller.reset_scores().await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, None);
+    }
+
+    #[tokio::test]
+    async fn test_shot_clock_follows_a_config_profile() {
+        let mut config = Config::default();
+        config.shot_clock.enabled = true;
+        config.profiles = vec![serde_yaml::from_str(r#"
+name: league9s
+label: League Nines
+periods: 2
+period_minutes: 9
+actions:
+  - { name: try, label: Try, points: 4, converted: true }
+  - { name: goal, label: Goal, points: 2, conversion: true }
+  - { name: field_goal, label: Field Goal, points: 1 }
+"#).unwrap()];
+        let controller = ScoreboardController::new(true, config);
+        controller.set_profile("league9s").await.unwrap();
+
+        controller.score_action("home", "try").await.unwrap();
+        controller.score_action("away", "field_goal").await.unwrap();
+        assert!(controller.get_state().await.shot_clock.is_some());
+        controller.score_action("home", "goal").await.unwrap();
+        assert_eq!(controller.get_state().await.shot_clock, None);
+    }
+
+    #[tokio::test]
+    async fn test_result_counts_tries() {
+        let controller = ScoreboardController::new(true, Config::default());
+        controller.set_teams("HRUFC".to_string(), "Henley Hawks".to_string()).await.unwrap();
+        controller.score_action("home", "try").await.unwrap();
+        controller.score_action("home", "conversion").await.unwrap();
+        controller.score_action("home", "penalty_try").await.unwrap();
+        controller.score_action("away", "penalty").await.unwrap();
+
+        let result = controller.result().await;
+        assert_eq!((result.home.as_str(), result.home_score, result.home_tries), ("HRUFC", 14, 2));
+        assert_eq!((result.away_score, result.away_tries), (3, 0));
+    }
+
+    #[tokio::test]
+    async fn test_half_time_score_is_kept() {
+        let mut config = Config::default();
+        config.half_time.alternate = true;
+        config.half_time.seconds = 1;
+        let controller = ScoreboardController::new(true, config);
+        controller.start_timer().await.unwrap();
+        controller.score_action("home", "try").await.unwrap();
+        assert!(controller.start_period().await.is_err());
+
+        let state = controller.end_period().await.unwrap();
+        assert!(state.interval && !state.timer_running);
+        assert_eq!(state.half_time, Some(PeriodScore { home: 5, away: 0 }));
+        controller.tick().await;
+        assert!(controller.get_state().await.half_time_label);
+        controller.tick().await;
+        assert!(!controller.get_state().await.half_time_label);
+
+        let state = controller.start_period().await.unwrap();
+        assert_eq!((state.period, state.timer_minutes, state.timer_running), (2, 40, true));
+        controller.score_action("away", "penalty").await.unwrap();
+        assert!(controller.end_period().await.is_err());
+        assert_eq!(controller.snapshot("main").await.half_time, Some(PeriodScore { home: 5, away: 0 }));
+    }
+
+    #[tokio::test]
+    async fn test_scorers_are_named_and_flashed() {
+        let controller = ScoreboardController::new(true, Config::default());
+        let squad = roster::parse_csv("11,John Smith\n15,Ed Brown\n").unwrap();
+        controller.set_roster("home", squad).await.unwrap();
+
+        let event = controller.score_action_by("home", "try", Some(11)).await.unwrap();
+        assert_eq!(event.player.unwrap().name, "John Smith");
+        let flash = controller.get_state().await.scorer_flash.unwrap();
+        assert_eq!((flash.team, flash.text.as_str(), flash.seconds), (Side::Home, "TRY 11 SMITH", 5));
+        assert!(controller.score_action_by("home", "try", Some(9)).await.is_err());
+        assert_eq!(controller.get_state().await.home_score, 5);
+
+        for _ in 0..5 {
+            controller.tick().await;
+        }
+        assert!(controller.get_state().await.scorer_flash.is_none());
+        let archived = controller.snapshot("main").await;
+        assert_eq!(archived.home.roster.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_hia_countdown_follows_the_match_clock() {
+        let mut config = Config::default();
+        config.replacements.hia_minutes = 1;
+        let controller = ScoreboardController::new(true, config);
+        controller.set_timer(20, 0).await.unwrap();
+        let hia = controller.substitute("home", SubstitutionKind::Hia, 12, 22).await.unwrap();
+        assert_eq!((hia.minute, hia.remaining), (20, Some(60)));
+        assert!(controller.substitute("home", SubstitutionKind::Permanent, 12, 18).await.is_err());
+        assert!(controller.substitute("home", SubstitutionKind::Permanent, 3, 22).await.is_err());
+        controller.give_card("away", "yellow", 4).await.unwrap();
+        assert!(controller.substitute("away", SubstitutionKind::Blood, 4, 19).await.is_err());
+
+        controller.tick().await;
+        assert_eq!(controller.substitutions().await[0].remaining, Some(60));
+        controller.start_timer().await.unwrap();
+        for _ in 0..60 {
+            controller.tick().await;
+        }
+        assert_eq!(controller.substitutions().await[0].status, SubstitutionStatus::Expired);
+        let timeline = controller.timeline().await;
+        assert!(matches!(&timeline[..], [TimelineEntry::Substitution(_), TimelineEntry::Expired(expired)] if expired.expired_minute == Some(21)));
+        assert!(controller.end_replacement(hia.id, true).await.is_err());
+        let ended = controller.end_replacement(hia.id, false).await.unwrap();
+        assert_eq!((ended.status, ended.ended_minute), (SubstitutionStatus::Permanent, Some(21)));
+        assert_eq!(controller.snapshot("main").await.substitutions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cards_follow_the_profile() {
+        let controller = ScoreboardController::new(true, Config::default());
+        controller.set_timer(30, 0).await.unwrap();
+        controller.give_card("home", "yellow", 6).await.unwrap();
+        let red = controller.give_card("away", "red_20", 3).await.unwrap();
+        assert_eq!(red.remaining, Some(1200));
+        assert!(controller.give_card("home", "green", 6).await.is_err());
+        let discipline = controller.discipline().await;
+        assert_eq!((discipline.home_players, discipline.away_players), (14, 14));
+
+        controller.start_timer().await.unwrap();
+        for _ in 0..600 {
+            controller.tick().await;
+        }
+        assert_eq!(controller.discipline().await.home_players, 15);
+        controller.remove_card(red.id).await.unwrap();
+        assert_eq!(controller.discipline().await.away_players, 15);
+
+        controller.set_profile("league").await.unwrap();
+        assert!(controller.give_card("away", "red_20", 3).await.is_err());
+        controller.give_card("away", "red", 3).await.unwrap();
+        assert_eq!(controller.discipline().await.away_players, 12);
+        assert_eq!(controller.snapshot("main").await.cards.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_shown_in_turn() {
+        let controller = ScoreboardController::new(true, Config::default());
+        assert!(controller.queue_message(" ".to_string(), 5, None, 0, Color::YELLOW).await.is_err());
+        controller.queue_message("BAR OPEN".to_string(), 2, None, 0, Color::YELLOW).await.unwrap();
+        let raffle = controller.queue_message("RAFFLE 42".to_string(), 1, Some(crate::protocol::windows::TIMER), 0, Color::WHITE).await.unwrap();
+        let shot_clock = Some(crate::protocol::windows::SHOT_CLOCK);
+        assert!(controller.queue_message("NO ROOM".to_string(), 1, shot_clock, 0, Color::WHITE).await.is_err());
+        let motm = controller.queue_message("MOTM 7".to_string(), 1, None, 0, Color::WHITE).await.unwrap();
+        controller.cancel_message(motm.id).await.unwrap();
+
+        controller.tick().await;
+        assert_eq!(controller.messages().await[0].seconds, 1);
+        controller.tick().await;
+        assert_eq!(controller.messages().await, vec![raffle]);
+        controller.tick().await;
+        assert!(controller.messages().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_sponsors_play_between_play() {
+        let mut config = Config::default();
+        config.sponsors.enabled = true;
+        config.sponsors.playlist = vec![crate::config::SponsorItem {
+            sponsor: "Brewery".to_string(),
+            text: "BREWERY".to_string(),
+            image: None,
+            seconds: 5,
+            effect: None,
+            color: None,
+        }];
+        let controller = ScoreboardController::new(true, config);
+        controller.tick().await;
+        controller.tick().await;
+        assert_eq!(controller.get_state().await.sponsor.unwrap().seconds, 4);
+
+        controller.start_timer().await.unwrap();
+        assert!(controller.get_state().await.sponsor.is_none());
+        controller.tick().await;
+        assert!(controller.get_state().await.sponsor.is_none());
+        assert_eq!(controller.sponsor_log().await[0].seconds, 1);
+
+        controller.queue_message("RAFFLE".to_string(), 1, None, 0, Color::WHITE).await.unwrap();
+        controller.finish_match("main").await;
+        controller.tick().await;
+        assert!(controller.get_state().await.sponsor.is_none());
+        controller.tick().await;
+        assert_eq!(controller.sponsors().await.current, Some(0));
+        assert!(controller.get_state().await.full_time);
+    }
+
+    #[tokio::test]
+    async fn test_celebrations_play_until_interrupted() {
+        let mut config = Config::default();
+        config.celebrations = vec![crate::config::CelebrationConfig {
+            action: "try".to_string(),
+            team: Some(Side::Home),
+            window: None,
+            frames: vec![
+                crate::config::CelebrationFrame { text: "TRY!".to_string(), seconds: 1, effect: None, color: None },
+                crate::config::CelebrationFrame { text: "{team}".to_string(), seconds: 2, effect: None, color: None },
+            ],
+        }, crate::config::CelebrationConfig {
+            action: "penalty".to_string(),
+            team: None,
+            window: Some("shot_clock".to_string()),
+            frames: vec![crate::config::CelebrationFrame { text: "3".to_string(), seconds: 1, effect: None, color: None }],
+        }];
+        let controller = ScoreboardController::new(true, config);
+        controller.score_action("away", "try").await.unwrap();
+        assert!(controller.get_state().await.celebration.is_empty());
+        // No board has a shot clock window to play it in
+        controller.score_action("home", "penalty").await.unwrap();
+        assert!(controller.get_state().await.celebration.is_empty());
+
+        controller.score_action("home", "try").await.unwrap();
+        assert_eq!(controller.get_state().await.celebration[0].text, "TRY!");
+        controller.tick().await;
+        let state = controller.get_state().await;
+        assert_eq!((state.celebration.len(), state.celebration[0].text.as_str()), (1, "HOME"));
+
+        controller.start_timer().await.unwrap();
+        assert!(controller.get_state().await.celebration.is_empty());
+        assert_eq!(controller.get_home_score().await, 8);
+    }
+
+    #[tokio::test]
+    async fn test_scores_are_recorded_and_reversible() {
+        let controller = ScoreboardController::new(true, Config::default());
+        assert!(controller.set_timer(12, 60).await.is_err());
+        assert!(controller.set_timer(MAX_TIMER_MINUTES + 1, 0).await.is_err());
+        controller.set_timer(12, 30).await.unwrap();
+        controller.score_action("home", "penalty_try").await.unwrap();
+        controller.score_action("away", "drop_goal").await.unwrap();
+        controller.score_action("home", "try").await.unwrap();
+        assert_eq!((controller.get_home_score().await, controller.get_away_score().await), (12, 3));
+
+        let events = controller.events().await;
+        assert_eq!(events.len(), 3);
+        assert_eq!((events[0].team, events[0].points, events[0].minute), (Side::Home, 7, 12));
+
+        assert_eq!(controller.undo_last().await.unwrap().action, "try");
+        assert_eq!(controller.undo_action("away", "drop_goal").await.unwrap(), 3);
+        assert_eq!((controller.get_home_score().await, controller.get_away_score().await), (7, 0));
+        assert_eq!(controller.events().await.len(), 1);
+
+        controller.reset_scores().await.unwrap();
+        assert!(controller.undo_last().await.is_err());
+        assert!(controller.undo_action("home", "try").await.is_err());
+        assert_eq!(controller.get_home_score().await, 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_undo_alongside_scoring() {
+        let controller = Arc::new(ScoreboardController::new(true, Config::default()));
+        for _ in 0..20 {
+            controller.score_action("home", "try").await.unwrap();
+        }
+        let scoring = tokio::spawn({
+            let controller = controller.clone();
+            async move {
+                for _ in 0..20 {
+                    controller.score_action("home", "try").await.unwrap();
+                }
+            }
+        });
+        let undoing = tokio::spawn({
+            let controller = controller.clone();
+            async move {
+                for _ in 0..20 {
+                    controller.undo_action("home", "try").await.unwrap();
+                }
+            }
+        });
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            scoring.await.unwrap();
+            undoing.await.unwrap();
+        }).await.expect("undo and scoring deadlocked");
+        assert_eq!(controller.get_home_score().await, 100);
+        assert_eq!(controller.events().await.len(), 20);
+    }
+}
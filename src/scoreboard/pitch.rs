@@ -0,0 +1,86 @@
+use std::path::Path;
+use std::sync::Arc;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::scoreboard::{ScoreboardController, SponsorFiles};
+
+/// A named pitch with its own match and boards
+#[derive(Clone)]
+pub struct Pitch {
+    pub id: String,
+    pub name: String,
+    pub controller: Arc<ScoreboardController>,
+}
+
+/// Summary of a pitch for the pitch picker
+#[derive(Debug, Clone, Serialize)]
+pub struct PitchSummary {
+    pub id: String,
+    pub name: String,
+    pub home_team: String,
+    pub away_team: String,
+    pub home_score: u16,
+    pub away_score: u16,
+    pub connected: bool,
+}
+
+/// All pitches hosted by the server
+#[derive(Clone)]
+pub struct Pitches {
+    pitches: Arc<Vec<Pitch>>,
+}
+
+impl Pitches {
+    /// Create a controller for every pitch in the config, each saving its
+    /// sponsor playlist and log in `sponsorship_dir`
+    pub fn new(simulation_mode: bool, config: &Config, sponsorship_dir: impl AsRef<Path>) -> Self {
+        let pitches = config.pitches()
+            .into_iter()
+            .map(|pitch| Pitch {
+                controller: Arc::new(
+                    ScoreboardController::new(simulation_mode, config.for_pitch(&pitch))
+                        .with_sponsor_files(SponsorFiles::new(&sponsorship_dir, &pitch.id)),
+                ),
+                id: pitch.id,
+                name: pitch.name,
+            })
+            .collect();
+
+        Self {
+            pitches: Arc::new(pitches),
+        }
+    }
+
+    /// Look up a pitch by ID
+    pub fn get(&self, id: &str) -> Option<&Pitch> {
+        self.pitches.iter().find(|pitch| pitch.id == id)
+    }
+
+    /// The first pitch, served by the unscoped `/api/...` routes
+    pub fn default_pitch(&self) -> &Pitch {
+        &self.pitches[0]
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Pitch> {
+        self.pitches.iter()
+    }
+
+    /// Current match and connection status of every pitch
+    pub async fn summaries(&self) -> Vec<PitchSummary> {
+        let mut summaries = Vec::with_capacity(self.pitches.len());
+        for pitch in self.pitches.iter() {
+            let state = pitch.controller.get_state().await;
+            summaries.push(PitchSummary {
+                id: pitch.id.clone(),
+                name: pitch.name.clone(),
+                home_team: state.home_team,
+                away_team: state.away_team,
+                home_score: state.home_score,
+                away_score: state.away_score,
+                connected: pitch.controller.is_connected().await,
+            });
+        }
+        summaries
+    }
+}
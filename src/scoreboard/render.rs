@@ -0,0 +1,205 @@
+use std::fmt::Write;
+use anyhow::Result;
+
+use crate::protocol::{Color, ScoreboardLayout, WindowData, WindowImage, DEFAULT_ALIGNMENT, font_points};
+use crate::scoreboard::display::WindowContent;
+use crate::scoreboard::font;
+
+/// Pixels of a virtual LED panel
+#[derive(Debug, Clone)]
+pub struct Frame {
+    width: u16,
+    height: u16,
+    pixels: Vec<Color>,
+}
+
+impl Frame {
+    /// A blank panel
+    pub fn new(width: u16, height: u16) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::BLACK; width as usize * height as usize],
+        }
+    }
+
+    /// A blank panel just big enough for every window of the layout
+    pub fn for_layout(layout: &ScoreboardLayout) -> Self {
+        let fullscreen = layout.fullscreen();
+        Self::new(fullscreen.width, fullscreen.height)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    /// Colour of a pixel
+    pub fn pixel(&self, x: u16, y: u16) -> &Color {
+        &self.pixels[y as usize * self.width as usize + x as usize]
+    }
+
+    /// Set a pixel, ignoring anything outside the panel
+    pub fn set_pixel(&mut self, x: u16, y: u16, color: &Color) {
+        if x < self.width && y < self.height {
+            self.pixels[y as usize * self.width as usize + x as usize] = color.clone();
+        }
+    }
+
+    /// Draw a line of text into a window the way the card would, clipped to the window
+    pub fn draw_text(&mut self, window: &WindowData, text: &str, color: &Color, font: u8, alignment: u8) {
+        let points = font_points(font);
+        let char_width = font::char_width(points) as i32;
+        let text_width = font::text_width(text, points) as i32;
+        let (width, height) = (window.width as i32, window.height as i32);
+
+        let left = match alignment & 0x03 {
+            1 => (width - text_width) / 2,
+            2 => width - text_width,
+            _ => 0,
+        };
+        let top = match (alignment >> 2) & 0x03 {
+            1 => (height - points as i32) / 2,
+            2 => height - points as i32,
+            _ => 0,
+        };
+
+        for (index, c) in text.chars().enumerate() {
+            let cell_left = left + index as i32 * char_width;
+            for cx in 0..char_width {
+                let x = cell_left + cx;
+                if !(0..width).contains(&x) {
+                    continue;
+                }
+                for cy in 0..points as i32 {
+                    let y = top + cy;
+                    if (0..height).contains(&y) && font::is_lit(c, cx as u16, cy as u16, points) {
+                        self.set_pixel(window.x + x as u16, window.y + y as u16, color);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Draw a picture into a window at its place, clipped to the window
+    pub fn draw_image(&mut self, window: &WindowData, image: &WindowImage) {
+        let picture = &image.picture;
+        for y in 0..picture.height {
+            for x in 0..picture.width {
+                let (wx, wy) = (image.x + x, image.y + y);
+                if wx < window.width && wy < window.height {
+                    self.set_pixel(window.x + wx, window.y + wy, picture.pixel(x, y));
+                }
+            }
+        }
+    }
+
+    /// Encode as an RGB PNG, each LED `scale` pixels square
+    pub fn to_png(&self, scale: u16) -> Result<Vec<u8>> {
+        let scale = scale.max(1) as usize;
+        let (width, height) = (self.width as usize * scale, self.height as usize * scale);
+
+        let mut data = Vec::with_capacity(width * height * 3);
+        for y in 0..height {
+            for x in 0..width {
+                let color = self.pixel((x / scale) as u16, (y / scale) as u16);
+                data.extend_from_slice(&[color.red, color.green, color.blue]);
+            }
+        }
+
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&data)?;
+        Ok(png)
+    }
+
+    /// Draw as an SVG of round LEDs, with unlit LEDs shown dimly
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{sw}" height="{sh}">"#,
+                r#"<defs><pattern id="led" width="1" height="1" patternUnits="userSpaceOnUse">"#,
+                r##"<circle cx="0.5" cy="0.5" r="0.4" fill="#1a1a1a"/></pattern></defs>"##,
+                r#"<rect width="{w}" height="{h}" fill="black"/><rect width="{w}" height="{h}" fill="url(#led)"/>"#,
+            ),
+            w = self.width,
+            h = self.height,
+            sw = self.width as u32 * 4,
+            sh = self.height as u32 * 4,
+        );
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let color = self.pixel(x, y);
+                if *color != Color::BLACK {
+                    let _ = write!(
+                        svg,
+                        r##"<circle cx="{}.5" cy="{}.5" r="0.45" fill="#{:02x}{:02x}{:02x}"/>"##,
+                        x, y, color.red, color.green, color.blue
+                    );
+                }
+            }
+        }
+
+        svg.push_str("</svg>");
+        svg
+    }
+}
+
+/// Render window contents onto a panel with the given layout. Scrolling
+/// text is drawn where it starts, left aligned.
+pub fn render(layout: &ScoreboardLayout, contents: &[(u8, WindowContent)]) -> Frame {
+    let mut frame = Frame::for_layout(layout);
+    for (window_id, content) in contents {
+        match (layout.window(*window_id), &content.image) {
+            (Some(window), Some(image)) => frame.draw_image(&window, image),
+            (Some(window), None) => frame.draw_text(&window, &content.text, &content.color, content.font, DEFAULT_ALIGNMENT),
+            (None, _) => {}
+        }
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::windows;
+
+    #[test]
+    fn test_render_standard_layout() {
+        let layout = ScoreboardLayout::standard_224x32();
+        let contents = vec![(windows::HOME_SCORE, WindowContent::new("1", Color::GREEN))];
+        let frame = render(&layout, &contents);
+        assert_eq!((frame.width(), frame.height()), (224, 32));
+
+        // The stem of the '1' at 16 points, in the home score window at x 96
+        assert_eq!(frame.pixel(96 + 4, 8), &Color::GREEN);
+        assert_eq!(frame.pixel(0, 0), &Color::BLACK);
+    }
+
+    #[test]
+    fn test_text_is_clipped_to_its_window() {
+        let layout = ScoreboardLayout::standard_224x32();
+        let long_name = WindowContent::new("WWWWWWWWWWWW", Color::WHITE);
+        let frame = render(&layout, &[(windows::HOME_NAME, long_name)]);
+
+        let lit_outside = (96..224).any(|x| (0..16).any(|y| frame.pixel(x, y) != &Color::BLACK));
+        assert!(!lit_outside);
+    }
+
+    #[test]
+    fn test_png_and_svg_output() {
+        let frame = render(&ScoreboardLayout::standard_224x32(), &[(0, WindowContent::new("A", Color::RED))]);
+        let png = frame.to_png(2).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+
+        let svg = frame.to_svg();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("#ff0000"));
+    }
+}
@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result, anyhow, bail};
+
+use crate::scoreboard::Side;
+
+/// Highest shirt number in a matchday squad
+pub const MAX_NUMBER: u8 = 23;
+
+/// A player in a team's matchday squad
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Player {
+    pub number: u8,
+    pub name: String,
+}
+
+impl Player {
+    /// Surname for the board, taken as the last word of the name
+    pub fn surname(&self) -> &str {
+        self.name.split_whitespace().last().unwrap_or("")
+    }
+}
+
+/// Both teams' squads for the match
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Rosters {
+    pub home: Vec<Player>,
+    pub away: Vec<Player>,
+}
+
+impl Rosters {
+    pub fn team(&self, side: Side) -> &[Player] {
+        match side {
+            Side::Home => &self.home,
+            Side::Away => &self.away,
+        }
+    }
+
+    /// Replace a team's squad, sorted by number
+    pub fn set(&mut self, side: Side, mut players: Vec<Player>) -> Result<()> {
+        for (index, player) in players.iter().enumerate() {
+            check_number(player.number)?;
+            if player.name.trim().is_empty() {
+                bail!("Player {} has no name", player.number);
+            }
+            if players[..index].iter().any(|other| other.number == player.number) {
+                bail!("Number {} is listed twice", player.number);
+            }
+        }
+        players.sort_by_key(|player| player.number);
+        match side {
+            Side::Home => self.home = players,
+            Side::Away => self.away = players,
+        }
+        Ok(())
+    }
+
+    /// The player wearing a number. Without a squad entered any number is
+    /// taken, with no name.
+    pub fn player(&self, side: Side, number: u8) -> Result<Player> {
+        check_number(number)?;
+        let squad = self.team(side);
+        if squad.is_empty() {
+            return Ok(Player { number, name: String::new() });
+        }
+        squad.iter()
+            .find(|player| player.number == number)
+            .cloned()
+            .ok_or_else(|| anyhow!("No number {} in the {} squad", number, side.as_str()))
+    }
+
+    pub fn clear(&mut self) {
+        self.home.clear();
+        self.away.clear();
+    }
+}
+
+fn check_number(number: u8) -> Result<()> {
+    if !(1..=MAX_NUMBER).contains(&number) {
+        bail!("Shirt numbers run from 1 to {}, not {}", MAX_NUMBER, number);
+    }
+    Ok(())
+}
+
+/// Parse a squad from CSV with `number` and `name` columns, or from rows of
+/// number and name without a header
+pub fn parse_csv(content: &str) -> Result<Vec<Player>> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .has_headers(false)
+        .trim(::csv::Trim::All)
+        .flexible(true)
+        .from_reader(content.as_bytes());
+
+    let mut records = reader.records().enumerate().peekable();
+    let (mut number, mut name) = (0, 1);
+    if let Some((_, Ok(first))) = records.peek()
+        && first.get(0).is_some_and(|field| field.parse::<u8>().is_err())
+    {
+        let headers: Vec<String> = first.iter().map(|header| header.to_ascii_lowercase()).collect();
+        let column = |names: &[&str]| headers.iter().position(|header| names.contains(&header.as_str()));
+        number = column(&["number", "no", "no.", "#", "shirt"]).ok_or_else(|| anyhow!("Missing 'number' column"))?;
+        name = column(&["name", "player"]).ok_or_else(|| anyhow!("Missing 'name' column"))?;
+        records.next();
+    }
+
+    let mut players = Vec::new();
+    for (index, record) in records {
+        let record = record?;
+        let line = index + 1;
+        let field = |column: usize| record.get(column).filter(|value| !value.is_empty());
+        let (Some(shirt), Some(player)) = (field(number), field(name)) else {
+            continue;
+        };
+        players.push(Player {
+            number: shirt.parse().with_context(|| format!("Line {}: invalid number '{}'", line, shirt))?,
+            name: player.to_string(),
+        });
+    }
+    Ok(players)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squad_from_csv() {
+        let players = parse_csv("Name,No\nJohn Smith,11\nAl Jones,4\n,\n").unwrap();
+        assert_eq!(players[0], Player { number: 11, name: "John Smith".to_string() });
+        assert_eq!(parse_csv("1,Ed Brown\n2,Tom Gray\n").unwrap().len(), 2);
+        assert!(parse_csv("number,name\neleven,Smith\n").is_err());
+
+        let mut rosters = Rosters::default();
+        assert_eq!(rosters.player(Side::Home, 11).unwrap().name, "");
+        rosters.set(Side::Home, players).unwrap();
+        assert_eq!(rosters.home[0].number, 4);
+        assert_eq!(rosters.player(Side::Home, 11).unwrap().surname(), "Smith");
+        assert!(rosters.player(Side::Home, 12).is_err());
+        assert!(rosters.player(Side::Away, 24).is_err());
+
+        let twice = vec![Player { number: 1, name: "A".to_string() }, Player { number: 1, name: "B".to_string() }];
+        assert!(rosters.set(Side::Away, twice).is_err());
+    }
+}
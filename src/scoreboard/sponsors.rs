@@ -0,0 +1,358 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+use anyhow::{Result, anyhow, bail};
+use log::{info, warn};
+
+use crate::config::{SponsorConfig, SponsorItem};
+use crate::protocol::{Color, effects};
+use crate::scoreboard::{BoardMessage, Image};
+
+/// Directory each pitch's sponsor playlist and log are kept in, next to
+/// config.yaml
+pub const SPONSORSHIP_DIR: &str = "sponsorship";
+
+/// Part of the day when the board is free for sponsors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Before the clock is first started
+    PreMatch,
+    /// A break between periods
+    HalfTime,
+    /// After the match is finished
+    PostMatch,
+}
+
+impl Phase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::PreMatch => "pre_match",
+            Phase::HalfTime => "half_time",
+            Phase::PostMatch => "post_match",
+        }
+    }
+}
+
+/// A sponsor's item having been on the board, for sponsor reporting
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Showing {
+    pub sponsor: String,
+    pub text: String,
+    pub phase: Phase,
+    pub started_at: DateTime<Local>,
+    /// Seconds it was on the board, short of the item's when play started
+    pub seconds: u16,
+}
+
+/// The playlist and what it is showing
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SponsorStatus {
+    pub enabled: bool,
+    pub playlist: Vec<SponsorItem>,
+    /// Index of the item on the board now
+    pub current: Option<usize>,
+    /// Seconds it has left
+    pub remaining: Option<u16>,
+}
+
+/// A playlist checked and with its logos loaded, ready to be set without
+/// reading files
+#[derive(Debug)]
+pub struct LoadedPlaylist {
+    config: SponsorConfig,
+    images: Vec<Option<Arc<Image>>>,
+}
+
+impl LoadedPlaylist {
+    pub fn load(config: SponsorConfig) -> Result<Self> {
+        let mut images = Vec::new();
+        for item in &config.playlist {
+            if item.seconds == 0 {
+                bail!("{}'s item has to be shown for at least a second", item.sponsor);
+            }
+            effect(item)?;
+            images.push(item.image.as_deref().map(Image::load).transpose()?.map(Arc::new));
+        }
+        Ok(Self { config, images })
+    }
+}
+
+/// Sponsor items looped between play, with a log of every showing
+#[derive(Debug, Default)]
+pub struct Playlist {
+    enabled: bool,
+    items: Vec<SponsorItem>,
+    /// Each item's logo, loaded when the playlist is set
+    images: Vec<Option<Arc<Image>>>,
+    /// Item on the board, or the next one to be shown
+    position: usize,
+    showing: Option<Showing>,
+    log: Vec<Showing>,
+    /// Showings logged since they were last taken to be saved
+    unsaved: Vec<Showing>,
+}
+
+impl Playlist {
+    pub fn new(config: &SponsorConfig) -> Result<Self> {
+        let mut playlist = Self::default();
+        playlist.set(LoadedPlaylist::load(config.clone())?);
+        Ok(playlist)
+    }
+
+    /// Replace the playlist, starting again from its first item
+    pub fn set(&mut self, loaded: LoadedPlaylist) {
+        self.pause();
+        self.enabled = loaded.config.enabled;
+        self.items = loaded.config.playlist;
+        self.images = loaded.images;
+        self.position = 0;
+    }
+
+    /// Whether there is anything to show in a phase
+    pub fn plays_in(&self, phase: Option<Phase>) -> bool {
+        self.enabled && !self.items.is_empty() && phase.is_some()
+    }
+
+    /// Advance by a second in a phase, or pause when it's none, returning
+    /// the message to show
+    pub fn tick(&mut self, phase: Option<Phase>) -> Option<BoardMessage> {
+        let Some(phase) = phase.filter(|_| self.plays_in(phase)) else {
+            self.pause();
+            return None;
+        };
+        match self.showing.as_mut() {
+            Some(showing) => {
+                showing.seconds += 1;
+                if showing.seconds >= self.items[self.position].seconds {
+                    self.pause();
+                    self.position = (self.position + 1) % self.items.len();
+                    self.start(phase);
+                }
+            }
+            None => self.start(phase),
+        }
+        self.message()
+    }
+
+    /// Take the item off the board, to be shown again from the start
+    /// next time
+    pub fn pause(&mut self) {
+        if let Some(showing) = self.showing.take().filter(|showing| showing.seconds > 0) {
+            info!("Sponsor {} shown for {}s ({}): '{}'", showing.sponsor, showing.seconds, showing.phase.as_str(), showing.text);
+            self.log.push(showing.clone());
+            self.unsaved.push(showing);
+        }
+    }
+
+    pub fn status(&self) -> SponsorStatus {
+        SponsorStatus {
+            enabled: self.enabled,
+            playlist: self.items.clone(),
+            current: self.showing.as_ref().map(|_| self.position),
+            remaining: self.message().map(|message| message.seconds),
+        }
+    }
+
+    /// Every item shown so far
+    pub fn log(&self) -> &[Showing] {
+        &self.log
+    }
+
+    /// Carry on from a log saved before the server restarted
+    pub fn restore_log(&mut self, log: Vec<Showing>) {
+        self.log = log;
+    }
+
+    /// Showings logged since the last call, for saving
+    pub fn take_unsaved(&mut self) -> Vec<Showing> {
+        std::mem::take(&mut self.unsaved)
+    }
+
+    fn start(&mut self, phase: Phase) {
+        let item = &self.items[self.position];
+        self.showing = Some(Showing {
+            sponsor: item.sponsor.clone(),
+            text: item.text.clone(),
+            phase,
+            started_at: Local::now(),
+            seconds: 0,
+        });
+    }
+
+    fn message(&self) -> Option<BoardMessage> {
+        let showing = self.showing.as_ref()?;
+        let item = &self.items[self.position];
+        Some(BoardMessage {
+            id: self.position as u32,
+            text: item.text.clone(),
+            seconds: item.seconds - showing.seconds,
+            window: None,
+            effect: effect(item).unwrap_or(effects::INSTANT),
+            color: item.color.clone().unwrap_or(Color::YELLOW),
+            image: self.images[self.position].clone(),
+        })
+    }
+}
+
+fn effect(item: &SponsorItem) -> Result<u8> {
+    match item.effect.as_deref() {
+        Some(name) => effects::by_name(name).ok_or_else(|| anyhow!("Unknown effect: {}", name)),
+        None => Ok(effects::INSTANT),
+    }
+}
+
+/// Where a pitch's playlist and log of showings are saved: the playlist
+/// last set through the API as YAML, and one JSON line per showing
+#[derive(Debug, Clone)]
+pub struct SponsorFiles {
+    playlist: PathBuf,
+    log: PathBuf,
+}
+
+impl SponsorFiles {
+    pub fn new(dir: impl AsRef<Path>, pitch: &str) -> Self {
+        let dir = dir.as_ref();
+        Self {
+            playlist: dir.join(format!("{}.yaml", pitch)),
+            log: dir.join(format!("{}.jsonl", pitch)),
+        }
+    }
+
+    /// The playlist last set through the API, if there is one
+    pub fn load_playlist(&self) -> Option<SponsorConfig> {
+        if !self.playlist.exists() {
+            return None;
+        }
+        let loaded = fs::read_to_string(&self.playlist)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| Ok(serde_yaml::from_str(&contents)?));
+        loaded.map_err(|e| warn!("Ignoring the saved playlist {}: {:#}", self.playlist.display(), e)).ok()
+    }
+
+    /// Showings logged so far, skipping any line that can't be read
+    pub fn load_log(&self) -> Vec<Showing> {
+        let Ok(contents) = fs::read_to_string(&self.log) else {
+            return Vec::new();
+        };
+        contents.lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line)
+                .map_err(|e| warn!("Skipping a line of {}: {}", self.log.display(), e))
+                .ok())
+            .collect()
+    }
+
+    pub async fn save_playlist(&self, config: &SponsorConfig) -> Result<()> {
+        create_parent(&self.playlist).await?;
+        tokio::fs::write(&self.playlist, serde_yaml::to_string(config)?).await?;
+        Ok(())
+    }
+
+    /// Add showings to the end of the log
+    pub async fn append_log(&self, showings: &[Showing]) -> Result<()> {
+        let mut lines = String::new();
+        for showing in showings {
+            lines.push_str(&serde_json::to_string(showing)?);
+            lines.push('\n');
+        }
+        create_parent(&self.log).await?;
+        let mut file = tokio::fs::OpenOptions::new().create(true).append(true).open(&self.log).await?;
+        file.write_all(lines.as_bytes()).await?;
+        // Tokio finishes writes in the background, so wait for this one
+        file.flush().await?;
+        Ok(())
+    }
+}
+
+async fn create_parent(path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    Ok(())
+}
+
+/// One row per showing, for sponsor reporting
+pub fn log_csv(log: &[Showing]) -> Result<String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(["started_at", "sponsor", "text", "phase", "seconds"])?;
+    for showing in log {
+        writer.write_record([
+            showing.started_at.format("%Y-%m-%d %H:%M:%S").to_string(),
+            showing.sponsor.clone(),
+            showing.text.clone(),
+            showing.phase.as_str().to_string(),
+            showing.seconds.to_string(),
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(sponsor: &str, seconds: u16) -> SponsorItem {
+        SponsorItem {
+            sponsor: sponsor.to_string(),
+            text: format!("{} - PROUD SPONSORS", sponsor.to_uppercase()),
+            image: None,
+            seconds,
+            effect: None,
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_playlist_loops_and_logs_showings() {
+        let mut playlist = Playlist::new(&SponsorConfig {
+            enabled: true,
+            playlist: vec![item("Brewery", 2), item("Garage", 1)],
+        }).unwrap();
+        assert_eq!(playlist.tick(Some(Phase::PreMatch)).unwrap().seconds, 2);
+        assert_eq!(playlist.tick(Some(Phase::PreMatch)).unwrap().seconds, 1);
+        assert_eq!(playlist.tick(Some(Phase::PreMatch)).unwrap().text, "GARAGE - PROUD SPONSORS");
+        assert_eq!(playlist.tick(Some(Phase::PreMatch)).unwrap().text, "BREWERY - PROUD SPONSORS");
+
+        // Play starting cuts the showing short, and the item is shown in
+        // full at the next break
+        assert!(playlist.tick(None).is_none());
+        assert_eq!(playlist.tick(Some(Phase::HalfTime)).unwrap().seconds, 2);
+        let log = playlist.log();
+        assert_eq!(log.len(), 2);
+        assert_eq!((log[0].sponsor.as_str(), log[0].seconds), ("Brewery", 2));
+        assert_eq!((log[1].sponsor.as_str(), log[1].seconds), ("Garage", 1));
+        assert!(log_csv(log).unwrap().contains("Garage,GARAGE - PROUD SPONSORS,pre_match,1"));
+
+        assert!(LoadedPlaylist::load(SponsorConfig { enabled: true, playlist: vec![item("Bank", 0)] }).is_err());
+        assert_eq!(playlist.status().current, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_sponsor_files_survive_a_restart() {
+        let dir = std::env::temp_dir().join(format!("sponsorship-{}", std::process::id()));
+        let files = SponsorFiles::new(&dir, "main");
+        assert!(files.load_playlist().is_none());
+        let config = SponsorConfig { enabled: true, playlist: vec![item("Brewery", 1)] };
+        files.save_playlist(&config).await.unwrap();
+        assert_eq!(files.load_playlist(), Some(config.clone()));
+
+        let mut playlist = Playlist::new(&config).unwrap();
+        for _ in 0..3 {
+            playlist.tick(Some(Phase::PreMatch));
+        }
+        playlist.tick(None);
+        let showings = playlist.take_unsaved();
+        assert_eq!(showings.len(), 2);
+        assert!(playlist.take_unsaved().is_empty());
+        files.append_log(&showings[..1]).await.unwrap();
+        fs::write(&files.log, format!("{}{{\n", fs::read_to_string(&files.log).unwrap())).unwrap();
+        files.append_log(&showings[1..]).await.unwrap();
+        assert_eq!(files.load_log(), showings);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,222 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, anyhow, bail};
+
+use crate::scoreboard::Side;
+use crate::scoreboard::roster::Player;
+
+/// Why a player left the field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubstitutionKind {
+    Permanent,
+    /// Temporary replacement for a head injury assessment
+    Hia,
+    /// Temporary replacement while bleeding is treated
+    Blood,
+}
+
+impl SubstitutionKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SubstitutionKind::Permanent => "Substitution",
+            SubstitutionKind::Hia => "HIA",
+            SubstitutionKind::Blood => "Blood",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubstitutionStatus {
+    /// The replacement stays on for the rest of the match
+    Permanent,
+    /// A temporary replacement with time left for the player to return
+    Active,
+    /// The player's time to return ran out
+    Expired,
+    /// The player came back on
+    Returned,
+}
+
+/// A player replaced during the match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Substitution {
+    pub id: u32,
+    pub team: Side,
+    pub kind: SubstitutionKind,
+    /// Player leaving the field
+    pub off: Player,
+    /// Replacement coming on
+    pub on: Player,
+    /// Match clock when the player left
+    pub minute: u8,
+    pub second: u8,
+    pub status: SubstitutionStatus,
+    /// Seconds of match clock left for the player to return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<u16>,
+    /// Match clock when a temporary replacement ended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_minute: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_second: Option<u8>,
+    /// Match clock when the player's time to return ran out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expired_minute: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expired_second: Option<u8>,
+    pub recorded_at: DateTime<Local>,
+}
+
+/// Substitutions made so far, in the order they happened
+#[derive(Debug, Default)]
+pub struct Substitutions {
+    substitutions: Vec<Substitution>,
+    next_id: u32,
+}
+
+impl Substitutions {
+    /// Record a substitution at a match clock's minute and second, giving
+    /// it the next ID. Temporary ones are given `window` seconds of match
+    /// clock for the player to return.
+    pub fn record(
+        &mut self,
+        team: Side,
+        kind: SubstitutionKind,
+        off: Player,
+        on: Player,
+        (minute, second): (u8, u8),
+        window: u16,
+    ) -> Result<Substitution> {
+        if off.number == on.number {
+            bail!("Number {} can't replace themselves", off.number);
+        }
+        let temporary = kind != SubstitutionKind::Permanent;
+        self.next_id += 1;
+        let substitution = Substitution {
+            id: self.next_id,
+            team,
+            kind,
+            off,
+            on,
+            minute,
+            second,
+            status: if temporary { SubstitutionStatus::Active } else { SubstitutionStatus::Permanent },
+            remaining: temporary.then_some(window),
+            ended_minute: None,
+            ended_second: None,
+            expired_minute: None,
+            expired_second: None,
+            recorded_at: Local::now(),
+        };
+        self.substitutions.push(substitution.clone());
+        Ok(substitution)
+    }
+
+    /// Count down the temporary replacements by a second of match clock,
+    /// returning those whose time has just run out at the match clock's
+    /// minute and second
+    pub fn tick(&mut self, (minute, second): (u8, u8)) -> Vec<Substitution> {
+        let mut expired = Vec::new();
+        for substitution in &mut self.substitutions {
+            if substitution.status != SubstitutionStatus::Active {
+                continue;
+            }
+            let remaining = substitution.remaining.unwrap_or(0).saturating_sub(1);
+            substitution.remaining = Some(remaining);
+            if remaining == 0 {
+                substitution.status = SubstitutionStatus::Expired;
+                substitution.expired_minute = Some(minute);
+                substitution.expired_second = Some(second);
+                expired.push(substitution.clone());
+            }
+        }
+        expired
+    }
+
+    /// End a temporary replacement, either with the player coming back on
+    /// or with the replacement staying on for good
+    pub fn end(&mut self, id: u32, returned: bool, (minute, second): (u8, u8)) -> Result<Substitution> {
+        let substitution = self.substitutions.iter_mut()
+            .find(|substitution| substitution.id == id)
+            .ok_or_else(|| anyhow!("No substitution {}", id))?;
+        match (substitution.status, returned) {
+            (SubstitutionStatus::Active, _) => {}
+            (SubstitutionStatus::Expired, true) => bail!("Time ran out for number {} to return", substitution.off.number),
+            (SubstitutionStatus::Expired, false) => {}
+            _ => bail!("Substitution {} isn't a temporary replacement still running", id),
+        }
+        substitution.status = if returned { SubstitutionStatus::Returned } else { SubstitutionStatus::Permanent };
+        substitution.remaining = None;
+        substitution.ended_minute = Some(minute);
+        substitution.ended_second = Some(second);
+        Ok(substitution.clone())
+    }
+
+    /// Numbers a team has on the field, starting with 1 to `starters` and
+    /// following its substitutions
+    pub fn on_field(&self, side: Side, starters: u8) -> Vec<u8> {
+        let mut numbers: Vec<u8> = (1..=starters).collect();
+        for substitution in self.substitutions.iter().filter(|substitution| substitution.team == side) {
+            numbers.retain(|number| *number != substitution.off.number);
+            numbers.push(substitution.on.number);
+            if substitution.status == SubstitutionStatus::Returned {
+                numbers.retain(|number| *number != substitution.on.number);
+                numbers.push(substitution.off.number);
+            }
+        }
+        numbers
+    }
+
+    /// Take back a substitution recorded by mistake
+    pub fn remove(&mut self, id: u32) -> Result<Substitution> {
+        let index = self.substitutions.iter()
+            .position(|substitution| substitution.id == id)
+            .ok_or_else(|| anyhow!("No substitution {}", id))?;
+        Ok(self.substitutions.remove(index))
+    }
+
+    pub fn substitutions(&self) -> &[Substitution] {
+        &self.substitutions
+    }
+
+    pub fn clear(&mut self) {
+        self.substitutions.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(number: u8) -> Player {
+        Player { number, name: String::new() }
+    }
+
+    #[test]
+    fn test_temporary_replacements_run_out() {
+        let mut substitutions = Substitutions::default();
+        let hia = substitutions.record(Side::Home, SubstitutionKind::Hia, player(12), player(22), (10, 0), 2).unwrap();
+        let blood = substitutions.record(Side::Away, SubstitutionKind::Blood, player(4), player(19), (10, 5), 3).unwrap();
+        substitutions.record(Side::Home, SubstitutionKind::Permanent, player(1), player(17), (11, 0), 2).unwrap();
+        assert!(substitutions.record(Side::Home, SubstitutionKind::Permanent, player(1), player(1), (11, 0), 2).is_err());
+
+        assert!(substitutions.tick((10, 1)).is_empty());
+        let expired = substitutions.tick((10, 2));
+        assert_eq!(expired.len(), 1);
+        assert_eq!((expired[0].id, expired[0].status), (hia.id, SubstitutionStatus::Expired));
+        assert_eq!((expired[0].expired_minute, expired[0].expired_second), (Some(10), Some(2)));
+        assert!(substitutions.end(hia.id, true, (12, 0)).is_err());
+        assert_eq!(substitutions.end(hia.id, false, (12, 0)).unwrap().status, SubstitutionStatus::Permanent);
+
+        let returned = substitutions.end(blood.id, true, (12, 0)).unwrap();
+        assert_eq!((returned.status, returned.remaining), (SubstitutionStatus::Returned, None));
+        assert!(substitutions.tick((12, 1)).is_empty());
+        let mut home = substitutions.on_field(Side::Home, 15);
+        home.sort();
+        assert_eq!(home, (2..=11).chain(13..=15).chain([17, 22]).collect::<Vec<_>>());
+        assert!(substitutions.on_field(Side::Away, 15).contains(&4));
+        assert!(substitutions.end(3, true, (12, 0)).is_err());
+    }
+}
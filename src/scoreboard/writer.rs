@@ -0,0 +1,376 @@
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::{mpsc, watch};
+use anyhow::Result;
+use log::{info, warn};
+
+use crate::config::TextFitConfig;
+use crate::protocol::{ScoreboardClient, Command, DisplayCommand, ScoreboardLayout, WindowData, windows};
+use crate::scoreboard::{BoardStatus, ScoreboardState};
+use crate::scoreboard::display::{WindowContent, window_contents, changed_windows};
+
+/// Work queued for a board's writer task
+#[derive(Debug, Clone)]
+pub enum Intent {
+    /// Show this state, replacing any window updates not yet sent
+    Show(Box<ScoreboardState>),
+    /// Send a one-off command
+    Command(Command),
+    /// Connect (or reconnect) and recreate the windows
+    Connect,
+    /// Switch to a different control card
+    Retarget { address: String, card_id: u8 },
+}
+
+/// Times a window's content is tried before it is left until it changes,
+/// so one window the card keeps refusing doesn't hold up the others
+const MAX_WINDOW_ATTEMPTS: u32 = 3;
+
+/// Work waiting to be sent to the board
+#[derive(Debug)]
+struct Outbox {
+    layout: ScoreboardLayout,
+    text_fit: TextFitConfig,
+    retarget: Option<(String, u8)>,
+    connect: bool,
+    commands: VecDeque<Command>,
+    /// Latest content wanted in each window
+    wanted: HashMap<u8, WindowContent>,
+    /// Content last sent to each window
+    sent: HashMap<u8, WindowContent>,
+    /// Failed attempts at sending each window's content
+    failures: HashMap<u8, (WindowContent, u32)>,
+    /// Whether the card has the single full screen window rather than the
+    /// layout's windows
+    fullscreen: bool,
+}
+
+impl Outbox {
+    fn new(layout: ScoreboardLayout, text_fit: TextFitConfig) -> Self {
+        Self {
+            layout,
+            text_fit,
+            retarget: None,
+            connect: false,
+            commands: VecDeque::new(),
+            wanted: HashMap::new(),
+            sent: HashMap::new(),
+            failures: HashMap::new(),
+            fullscreen: false,
+        }
+    }
+
+    fn push(&mut self, intent: Intent) {
+        match intent {
+            Intent::Show(state) => self.wanted = window_contents(&state, &self.layout, &self.text_fit).into_iter().collect(),
+            Intent::Command(command) => self.commands.push_back(command),
+            Intent::Connect => self.connect = true,
+            Intent::Retarget { address, card_id } => {
+                self.retarget = Some((address, card_id));
+                self.connect = true;
+            }
+        }
+    }
+
+    /// Windows the card needs for the wanted content: the whole board for a
+    /// full screen message, otherwise the layout
+    fn windows(&self) -> (bool, Vec<WindowData>) {
+        if self.wanted.contains_key(&windows::FULLSCREEN) {
+            (true, vec![self.layout.fullscreen()])
+        } else {
+            (false, self.layout.all_windows())
+        }
+    }
+
+    /// The next window to send, the timer first so the clock never waits
+    /// behind other updates. Windows that failed go after the rest, and
+    /// are left out once they've used up their attempts.
+    fn next_window(&self) -> Option<(u8, WindowContent)> {
+        let wanted = self.wanted.iter()
+            .map(|(window_id, content)| (*window_id, content.clone()))
+            .collect();
+        changed_windows(&self.sent, wanted)
+            .into_iter()
+            .map(|(window_id, content)| (self.failures(window_id, &content), window_id, content))
+            .filter(|(failures, _, _)| *failures < MAX_WINDOW_ATTEMPTS)
+            .min_by_key(|(failures, window_id, _)| (*failures, *window_id != windows::TIMER, *window_id))
+            .map(|(_, window_id, content)| (window_id, content))
+    }
+
+    /// Failed attempts at sending this content to a window
+    fn failures(&self, window_id: u8, content: &WindowContent) -> u32 {
+        self.failures.get(&window_id)
+            .filter(|(failed, _)| failed == content)
+            .map_or(0, |(_, attempts)| *attempts)
+    }
+
+    /// Count a failed attempt, returning how many there have been
+    fn failed(&mut self, window_id: u8, content: WindowContent) -> u32 {
+        let attempts = self.failures(window_id, &content) + 1;
+        self.failures.insert(window_id, (content, attempts));
+        attempts
+    }
+}
+
+/// Task that owns a board's client and is the only thing writing to it
+pub struct BoardWriter {
+    name: String,
+    client: ScoreboardClient,
+    outbox: Outbox,
+    intents: mpsc::UnboundedReceiver<Intent>,
+    status: watch::Sender<BoardStatus>,
+}
+
+impl BoardWriter {
+    pub fn new(
+        name: String,
+        layout: ScoreboardLayout,
+        text_fit: TextFitConfig,
+        client: ScoreboardClient,
+        intents: mpsc::UnboundedReceiver<Intent>,
+        status: watch::Sender<BoardStatus>,
+    ) -> Self {
+        Self {
+            name,
+            client,
+            outbox: Outbox::new(layout, text_fit),
+            intents,
+            status,
+        }
+    }
+
+    /// Process intents until every handle to the board is dropped
+    pub async fn run(mut self) {
+        while let Some(intent) = self.intents.recv().await {
+            self.outbox.push(intent);
+            loop {
+                // Pick up anything queued meanwhile so stale updates are coalesced
+                while let Ok(intent) = self.intents.try_recv() {
+                    self.outbox.push(intent);
+                }
+                if !self.send_next().await {
+                    break;
+                }
+            }
+        }
+        self.client.disconnect().await;
+    }
+
+    /// Send the most urgent piece of work. Returns false when there is
+    /// nothing more that can be sent.
+    async fn send_next(&mut self) -> bool {
+        if let Some((address, card_id)) = self.outbox.retarget.take() {
+            self.client.disconnect().await;
+            self.client = ScoreboardClient::new(address, card_id);
+            self.outbox.sent.clear();
+            self.publish_status();
+            return true;
+        }
+
+        if self.outbox.connect {
+            self.outbox.connect = false;
+            if let Err(e) = self.connect().await {
+                warn!("Failed to initialize board '{}': {}", self.name, e);
+            }
+            self.publish_status();
+            return true;
+        }
+
+        if !self.client.is_connected() {
+            // Reconnection is left to the connection monitor
+            if !self.outbox.commands.is_empty() {
+                warn!("Board '{}' is offline, dropping {} command(s)", self.name, self.outbox.commands.len());
+                self.outbox.commands.clear();
+            }
+            return false;
+        }
+
+        let result = if let Some(command) = self.outbox.commands.pop_front() {
+            self.client.send_command(command).await.map(|_| ())
+        } else if self.outbox.windows().0 != self.outbox.fullscreen {
+            self.create_windows().await
+        } else if let Some((window_id, content)) = self.outbox.next_window() {
+            let index = self.outbox.layout.window_index(window_id).unwrap_or(window_id);
+            let result = send_content(&mut self.client, index, &content).await;
+            match &result {
+                Ok(()) => {
+                    self.outbox.failures.remove(&window_id);
+                    self.outbox.sent.insert(window_id, content);
+                }
+                Err(_) => {
+                    if self.outbox.failed(window_id, content) == MAX_WINDOW_ATTEMPTS {
+                        warn!("Board '{}' refused window {} {} times, leaving it until it changes", self.name, window_id, MAX_WINDOW_ATTEMPTS);
+                    }
+                }
+            }
+            result
+        } else {
+            return false;
+        };
+
+        if let Err(e) = result {
+            warn!("Board '{}' update failed: {}", self.name, e);
+            self.publish_status();
+        }
+        true
+    }
+
+    /// Connect and create the windows
+    async fn connect(&mut self) -> Result<()> {
+        info!("Initializing board '{}'...", self.name);
+        self.client.disconnect().await;
+        self.client.connect().await?;
+
+        self.create_windows().await?;
+        info!("Board '{}' initialized successfully", self.name);
+        Ok(())
+    }
+
+    /// Split the display into the windows the wanted content needs
+    async fn create_windows(&mut self) -> Result<()> {
+        let (fullscreen, windows) = self.outbox.windows();
+        let create_cmd = Command::DisplayMessage(DisplayCommand::CreateWindows(windows));
+        self.client.send_command(create_cmd).await?;
+
+        // Creating the windows clears them, and gives every window a fresh
+        // start
+        self.outbox.sent.clear();
+        self.outbox.failures.clear();
+        self.outbox.fullscreen = fullscreen;
+        Ok(())
+    }
+
+    fn publish_status(&self) {
+        self.status.send_replace(BoardStatus {
+            name: self.name.clone(),
+            address: self.client.address().to_string(),
+            card_id: self.client.card_id(),
+            connected: self.client.is_connected(),
+        });
+    }
+}
+
+/// Helper method to send text, or an image, to a window numbered in
+/// creation order
+async fn send_content(client: &mut ScoreboardClient, window_id: u8, content: &WindowContent) -> Result<()> {
+    let cmd = match &content.image {
+        Some(image) => Command::DisplayMessage(DisplayCommand::SendImage {
+            window_id,
+            mode: content.effect,
+            speed: content.speed,
+            image: image.clone(),
+        }),
+        None => Command::DisplayMessage(DisplayCommand::SendPureText {
+            window_id,
+            text: content.text.clone(),
+            color: content.color.clone(),
+            font: content.font,
+            effect: content.effect,
+            speed: content.speed,
+        }),
+    };
+    client.send_command(cmd).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::TimeCommand;
+
+    fn outbox() -> Outbox {
+        Outbox::new(ScoreboardLayout::standard_224x32(), TextFitConfig::default())
+    }
+
+    #[test]
+    fn test_stale_window_updates_are_coalesced() {
+        let mut outbox = outbox();
+        for score in 1..=3 {
+            outbox.push(Intent::Show(Box::new(ScoreboardState {
+                home_score: score,
+                ..ScoreboardState::default()
+            })));
+        }
+
+        let mut sent = Vec::new();
+        while let Some((window_id, content)) = outbox.next_window() {
+            outbox.sent.insert(window_id, content.clone());
+            sent.push((window_id, content.text));
+        }
+
+        let home_scores: Vec<_> = sent.iter().filter(|(id, _)| *id == windows::HOME_SCORE).collect();
+        assert_eq!(home_scores, vec![&(windows::HOME_SCORE, "3".to_string())]);
+        assert_eq!(sent.len(), 5);
+    }
+
+    #[test]
+    fn test_timer_window_goes_first() {
+        let mut outbox = outbox();
+        outbox.push(Intent::Show(Box::default()));
+        while let Some((window_id, content)) = outbox.next_window() {
+            outbox.sent.insert(window_id, content);
+        }
+
+        outbox.push(Intent::Show(Box::new(ScoreboardState {
+            home_score: 7,
+            timer_seconds: 1,
+            ..ScoreboardState::default()
+        })));
+        assert_eq!(outbox.next_window().map(|(id, _)| id), Some(windows::TIMER));
+    }
+
+    #[test]
+    fn test_full_screen_messages_replace_the_layout() {
+        let mut outbox = outbox();
+        outbox.push(Intent::Show(Box::new(ScoreboardState {
+            messages: vec![crate::scoreboard::BoardMessage {
+                id: 1,
+                text: "RAFFLE 42".to_string(),
+                seconds: 5,
+                window: None,
+                effect: 0,
+                color: crate::protocol::Color::YELLOW,
+                image: None,
+            }],
+            ..ScoreboardState::default()
+        })));
+        assert_eq!(outbox.windows().1.len(), 1);
+        assert_eq!(outbox.next_window().map(|(id, content)| (id, content.text)), Some((windows::FULLSCREEN, "RAFFLE 42".to_string())));
+
+        outbox.push(Intent::Show(Box::default()));
+        assert_eq!(outbox.windows(), (false, ScoreboardLayout::standard_224x32().all_windows()));
+        assert_eq!(outbox.wanted.len(), 5);
+    }
+
+    #[test]
+    fn test_failing_windows_give_way() {
+        let mut outbox = outbox();
+        outbox.push(Intent::Show(Box::default()));
+        let (window_id, content) = outbox.next_window().unwrap();
+        assert_eq!(window_id, windows::TIMER);
+
+        outbox.failed(window_id, content.clone());
+        assert_ne!(outbox.next_window().map(|(id, _)| id), Some(windows::TIMER));
+        while let Some((id, content)) = outbox.next_window().filter(|(id, _)| *id != windows::TIMER) {
+            outbox.sent.insert(id, content);
+        }
+        for _ in 1..MAX_WINDOW_ATTEMPTS {
+            assert_eq!(outbox.next_window().map(|(id, _)| id), Some(windows::TIMER));
+            outbox.failed(window_id, content.clone());
+        }
+        assert!(outbox.next_window().is_none());
+
+        // New content for the window is tried again
+        outbox.push(Intent::Show(Box::new(ScoreboardState { timer_seconds: 1, ..ScoreboardState::default() })));
+        assert_eq!(outbox.next_window().map(|(id, _)| id), Some(windows::TIMER));
+    }
+
+    #[test]
+    fn test_commands_keep_their_order() {
+        let mut outbox = outbox();
+        outbox.push(Intent::Command(Command::TimeControl(TimeCommand::StartStop(true))));
+        outbox.push(Intent::Command(Command::TimeControl(TimeCommand::StartStop(false))));
+
+        assert!(matches!(outbox.commands.pop_front(), Some(Command::TimeControl(TimeCommand::StartStop(true)))));
+        assert!(matches!(outbox.commands.pop_front(), Some(Command::TimeControl(TimeCommand::StartStop(false)))));
+    }
+}
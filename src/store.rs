@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::{Mutex, MutexGuard};
+use anyhow::Result;
+use log::warn;
+
+/// A list persisted to a YAML file, saved after every change
+pub struct YamlStore<T> {
+    path: PathBuf,
+    items: Mutex<Vec<T>>,
+    /// Held while saving, so saves land in the order the changes were made
+    saving: Mutex<()>,
+}
+
+impl<T: Serialize + DeserializeOwned> YamlStore<T> {
+    /// Load the list from a file, starting empty if it doesn't exist. A
+    /// file that can't be read is set aside, so the store can still be
+    /// used without losing it.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let items = if path.exists() {
+            read(&path).unwrap_or_else(|e| {
+                let aside = path.with_extension("yaml.invalid");
+                warn!("Starting with an empty {}, kept as {}: {:#}", path.display(), aside.display(), e);
+                if let Err(e) = fs::rename(&path, &aside) {
+                    warn!("Failed to set aside {}: {}", path.display(), e);
+                }
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        Self {
+            path,
+            items: Mutex::new(items),
+            saving: Mutex::new(()),
+        }
+    }
+
+    /// The items, for reading
+    pub async fn lock(&self) -> MutexGuard<'_, Vec<T>> {
+        self.items.lock().await
+    }
+
+    /// Change the items and save them. The file is written once the lock on
+    /// them is released, and not at all when the change fails.
+    pub async fn update<R>(&self, change: impl FnOnce(&mut Vec<T>) -> Result<R>) -> Result<R> {
+        let _saving = self.saving.lock().await;
+        let (result, contents) = {
+            let mut items = self.items.lock().await;
+            let result = change(&mut items)?;
+            (result, serde_yaml::to_string(&*items)?)
+        };
+        tokio::fs::write(&self.path, contents).await?;
+        Ok(result)
+    }
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
+    Ok(serde_yaml::from_str(&fs::read_to_string(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unreadable_files_are_set_aside() {
+        let path = std::env::temp_dir().join(format!("store-{}.yaml", std::process::id()));
+        let store: YamlStore<String> = YamlStore::load(&path);
+        store.update(|items| {
+            items.push("first".to_string());
+            Ok(())
+        }).await.unwrap();
+        assert!(store.update(|_| -> Result<()> { anyhow::bail!("Refused") }).await.is_err());
+        assert_eq!(*YamlStore::<String>::load(&path).lock().await, vec!["first".to_string()]);
+
+        fs::write(&path, "- [").unwrap();
+        assert!(YamlStore::<String>::load(&path).lock().await.is_empty());
+        let aside = path.with_extension("yaml.invalid");
+        assert_eq!(fs::read_to_string(&aside).unwrap(), "- [");
+
+        fs::remove_file(aside).unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use anyhow::{Result, bail};
+
+use crate::protocol::Color;
+use crate::store::YamlStore;
+
+/// File the registry is kept in, next to config.yaml
+pub const TEAMS_FILE: &str = "teams.yaml";
+
+/// A team known to the club
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Team {
+    pub id: String,
+    #[serde(flatten)]
+    pub details: TeamDetails,
+}
+
+/// Everything about a team except its ID, as sent to the API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamDetails {
+    pub name: String,
+    /// Name for the board when the full name doesn't fit
+    #[serde(default)]
+    pub short_name: Option<String>,
+    /// Colour of the team's name on the board
+    #[serde(default = "default_primary_color")]
+    pub primary_color: Color,
+    #[serde(default = "default_secondary_color")]
+    pub secondary_color: Color,
+    /// Crest image as a data URL
+    #[serde(default)]
+    pub crest: Option<String>,
+}
+
+fn default_primary_color() -> Color {
+    Color::WHITE
+}
+
+fn default_secondary_color() -> Color {
+    Color::BLACK
+}
+
+/// Teams persisted to a YAML file, saved after every change
+pub struct TeamRegistry {
+    teams: YamlStore<Team>,
+}
+
+impl TeamRegistry {
+    /// Load the registry from a file, see [`YamlStore::load`]
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        Self { teams: YamlStore::load(path) }
+    }
+
+    /// All teams, sorted by name
+    pub async fn list(&self) -> Vec<Team> {
+        let mut teams = self.teams.lock().await.clone();
+        teams.sort_by(|a, b| a.details.name.cmp(&b.details.name));
+        teams
+    }
+
+    /// Look up a team by ID
+    pub async fn get(&self, id: &str) -> Option<Team> {
+        self.teams.lock().await.iter().find(|team| team.id == id).cloned()
+    }
+
+    /// Find a team by its name or short name, ignoring case
+    pub async fn find_by_name(&self, name: &str) -> Option<Team> {
+        let name = name.trim();
+        self.teams.lock().await
+            .iter()
+            .find(|team| {
+                team.details.name.eq_ignore_ascii_case(name)
+                    || team.details.short_name.as_deref().is_some_and(|short| short.eq_ignore_ascii_case(name))
+            })
+            .cloned()
+    }
+
+    /// Add a team, giving it an ID based on its name
+    pub async fn create(&self, details: TeamDetails) -> Result<Team> {
+        validate(&details)?;
+        self.teams.update(|teams| {
+            let base = slug(&details.name);
+            let mut id = base.clone();
+            let mut suffix = 2;
+            while teams.iter().any(|team| team.id == id) {
+                id = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+
+            let team = Team { id, details };
+            teams.push(team.clone());
+            Ok(team)
+        }).await
+    }
+
+    /// Replace a team's details, keeping its ID
+    pub async fn update(&self, id: &str, details: TeamDetails) -> Result<Team> {
+        validate(&details)?;
+        self.teams.update(|teams| {
+            let Some(team) = teams.iter_mut().find(|team| team.id == id) else {
+                bail!("No team with ID '{}'", id);
+            };
+            team.details = details;
+            Ok(team.clone())
+        }).await
+    }
+
+    /// Remove a team
+    pub async fn delete(&self, id: &str) -> Result<()> {
+        self.teams.update(|teams| {
+            let count = teams.len();
+            teams.retain(|team| team.id != id);
+            if teams.len() == count {
+                bail!("No team with ID '{}'", id);
+            }
+            Ok(())
+        }).await
+    }
+}
+
+fn validate(details: &TeamDetails) -> Result<()> {
+    if details.name.trim().is_empty() {
+        bail!("Team name cannot be empty");
+    }
+    Ok(())
+}
+
+/// Lowercase ASCII letters and digits of a name, joined by dashes
+pub(crate) fn slug(name: &str) -> String {
+    let slug = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() { "team".to_string() } else { slug }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn details(name: &str) -> TeamDetails {
+        TeamDetails {
+            name: name.to_string(),
+            short_name: None,
+            primary_color: Color::RED,
+            secondary_color: Color::WHITE,
+            crest: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_teams_are_persisted() {
+        let path = std::env::temp_dir().join(format!("teams-{}.yaml", std::process::id()));
+        let registry = TeamRegistry::load(&path);
+
+        let first = registry.create(details("Henley Hawks")).await.unwrap();
+        let second = registry.create(details("Henley Hawks")).await.unwrap();
+        assert_eq!(first.id, "henley-hawks");
+        assert_eq!(second.id, "henley-hawks-2");
+
+        let mut renamed = details("Henley Hawks II");
+        renamed.short_name = Some("HAWKS II".to_string());
+        registry.update(&second.id, renamed.clone()).await.unwrap();
+        assert!(registry.create(details(" ")).await.is_err());
+
+        let reloaded = TeamRegistry::load(&path);
+        assert_eq!(reloaded.get(&second.id).await.unwrap().details, renamed);
+
+        reloaded.delete(&first.id).await.unwrap();
+        assert!(reloaded.delete(&first.id).await.is_err());
+        assert_eq!(TeamRegistry::load(&path).list().await.len(), 1);
+
+        fs::remove_file(path).unwrap();
+    }
+}
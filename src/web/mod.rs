@@ -1,15 +1,78 @@
 use warp::{Filter, Rejection, Reply};
+use warp::filters::BoxedFilter;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use std::convert::Infallible;
+use std::time::Duration;
 use log::{info, error};
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::BroadcastStream;
 
-use crate::scoreboard::ScoreboardController;
+use crate::config::{Config, SponsorConfig};
+use crate::protocol::{Color, effects, windows};
+use crate::protocol::discovery::{self, DiscoveredCard};
+use crate::scoreboard::{ScoreboardController, ScoreboardState, BoardStatus, Image, Pitches, Player, SubstitutionKind, roster, sponsors};
+use crate::teams::{TeamDetails, TeamRegistry};
+use crate::fixtures::{self, FixtureStore, ImportFormat};
+use crate::league::LeagueTable;
+use crate::archive::{self, ArchivedMatch, MatchArchive};
+
+mod report;
 
 #[derive(Debug, Deserialize)]
 pub struct TeamUpdate {
     pub home_team: String,
     pub away_team: String,
+    #[serde(default)]
+    pub home_short_name: Option<String>,
+    #[serde(default)]
+    pub away_short_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TeamSelection {
+    pub home_id: String,
+    pub away_id: String,
+}
+
+/// Formats archived matches can be exported in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportQuery {
+    #[serde(default)]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TableQuery {
+    /// Only count results in this competition
+    #[serde(default)]
+    pub competition: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FixturesQuery {
+    /// Include fixtures that have already kicked off
+    #[serde(default)]
+    pub all: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Detected from the file when omitted
+    pub format: Option<ImportFormat>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResult {
+    pub imported: usize,
 }
 
 #[derive(Debug, Deserialize)]
@@ -25,8 +88,106 @@ pub struct TimerUpdate {
 }
 
 #[derive(Debug, Deserialize)]
-pub struct TeamAction {
+pub struct ScoreActionRequest {
+    pub team: String,
+    /// Name of an action of the active rule profile
+    pub action: String,
+    /// Take back the team's latest score of this action instead
+    #[serde(default)]
+    pub undo: bool,
+    /// Shirt number of the scorer
+    #[serde(default)]
+    pub player: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MessageRequest {
+    pub text: String,
+    /// Seconds it stays on the board
+    #[serde(default = "default_message_duration")]
+    pub duration: u16,
+    /// Name of the window to replace
+    #[serde(default)]
+    pub window: Option<String>,
+    /// Replace every window, the default when no window is named
+    #[serde(default)]
+    pub fullscreen: bool,
+    /// Name of a display effect, instant when omitted
+    #[serde(default)]
+    pub effect: Option<String>,
+    #[serde(default)]
+    pub color: Option<Color>,
+}
+
+fn default_message_duration() -> u16 {
+    10
+}
+
+/// Where and how long an uploaded image is shown, as for a message
+#[derive(Debug, Deserialize)]
+pub struct ImageQuery {
+    /// Stands for the image in the message queue
+    #[serde(default = "default_image_name")]
+    pub name: String,
+    #[serde(default = "default_message_duration")]
+    pub duration: u16,
+    #[serde(default)]
+    pub window: Option<String>,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub effect: Option<String>,
+}
+
+fn default_image_name() -> String {
+    "image".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CardRequest {
+    pub team: String,
+    /// One of the profile's sin bin cards, `red` or `red_20`
+    pub card: String,
+    /// Shirt number of the player shown the card
+    pub player: u8,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubstitutionRequest {
     pub team: String,
+    pub kind: SubstitutionKind,
+    /// Shirt number of the player leaving the field
+    pub off: u8,
+    /// Shirt number of the replacement
+    pub on: u8,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProfileSelection {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CardSelection {
+    pub address: String,
+    pub card_id: u8,
+    /// Board to point at the card, defaults to the first board
+    pub board: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewQuery {
+    /// `png` or `svg` (the default)
+    pub format: Option<String>,
+    /// Size of each LED in pixels for PNG output
+    pub scale: Option<u16>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusResponse {
+    #[serde(flatten)]
+    pub state: ScoreboardState,
+    pub boards: Vec<BoardStatus>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -47,110 +208,1062 @@ pub struct ApiResponse<T> {
     pub error: Option<String>,
 }
 
-impl<T> ApiResponse<T> {
-    pub fn success(data: T) -> Self {
-        Self {
-            success: true,
-            data: Some(data),
-            error: None,
-        }
-    }
+impl<T> ApiResponse<T> {
+    pub fn success(data: T) -> Self {
+        Self {
+            success: true,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    pub fn error(message: String) -> Self {
+        Self {
+            success: false,
+            data: None,
+            error: Some(message),
+        }
+    }
+}
+
+// Helper function to create a JSON reply with proper type annotations
+fn json_reply<T: serde::Serialize>(response: ApiResponse<T>) -> Result<warp::reply::Json, Infallible> {
+    Ok(warp::reply::json(&response))
+}
+
+/// Create the web server routes
+pub fn create_routes(
+    pitches: Pitches,
+    teams: Arc<TeamRegistry>,
+    fixtures: Arc<FixtureStore>,
+    league: Arc<LeagueTable>,
+    archive: Arc<MatchArchive>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let cors = warp::cors()
+        .allow_any_origin()
+        .allow_headers(vec!["content-type"])
+        .allow_methods(vec!["GET", "POST", "PUT", "DELETE"]);
+
+    let static_files = warp::path("static")
+        .and(warp::fs::dir("static"));
+
+    let index = warp::path::end()
+        .and(warp::get())
+        .and(warp::fs::file("static/index.html"));
+
+    let config_page = warp::path("config")
+        .and(warp::get())
+        .and(warp::fs::file("static/config.html"));
+
+    let pitches_page = warp::path("pitches")
+        .and(warp::get())
+        .and(warp::fs::file("static/pitches.html"));
+
+    let teams_page = warp::path("teams")
+        .and(warp::get())
+        .and(warp::fs::file("static/teams.html"));
+
+    let league_page = warp::path("league")
+        .and(warp::get())
+        .and(warp::fs::file("static/league.html"));
+
+    let matches_page = warp::path("matches")
+        .and(warp::get())
+        .and(warp::fs::file("static/matches.html"));
+
+    // Unscoped /api/... routes drive the first pitch, /api/pitches/{id}/...
+    // drive the named one
+    let default_pitch = pitches.default_pitch();
+    let mut pitch_routes = match_routes(default_pitch.id.clone(), default_pitch.controller.clone(), teams.clone(), fixtures.clone(), league.clone(), archive.clone());
+    for pitch in pitches.iter() {
+        let scoped = warp::path("pitches")
+            .and(warp::path(pitch.id.clone()))
+            .and(match_routes(pitch.id.clone(), pitch.controller.clone(), teams.clone(), fixtures.clone(), league.clone(), archive.clone()));
+        pitch_routes = pitch_routes.or(scoped).unify().boxed();
+    }
+
+    let api_routes = warp::path("api").and(
+        list_pitches(pitches.clone())
+            .or(team_registry_routes(teams))
+            .or(fixture_routes(fixtures))
+            .or(league_routes(league))
+            .or(archive_routes(archive))
+            .or(pitch_routes)
+    );
+
+    index
+        .or(config_page)
+        .or(pitches_page)
+        .or(teams_page)
+        .or(league_page)
+        .or(matches_page)
+        .or(static_files)
+        .or(api_routes)
+        .with(cors)
+}
+
+/// API routes controlling the match on a single pitch
+fn match_routes(
+    pitch_id: String,
+    controller: Arc<ScoreboardController>,
+    teams: Arc<TeamRegistry>,
+    fixtures: Arc<FixtureStore>,
+    league: Arc<LeagueTable>,
+    archive: Arc<MatchArchive>,
+) -> BoxedFilter<(Box<dyn Reply>,)> {
+    get_status(controller.clone())
+        .or(set_teams(controller.clone()))
+        .or(select_teams(controller.clone(), teams.clone()))
+        .or(setup_fixture(controller.clone(), teams, fixtures))
+        .or(set_scores(controller.clone()))
+        .or(increment_home_score(controller.clone()))
+        .or(increment_away_score(controller.clone()))
+        .or(reset_scores(controller.clone()))
+        .or(set_timer(controller.clone()))
+        .or(start_timer(controller.clone()))
+        .or(stop_timer(controller.clone()))
+        .or(reset_timer(controller.clone()))
+        .or(end_period(controller.clone()))
+        .or(start_period(controller.clone()))
+        .or(start_shot_clock(controller.clone()))
+        .or(cancel_shot_clock(controller.clone()))
+        .or(score_action(controller.clone()))
+        .or(undo_score(controller.clone()))
+        .or(score_events(controller.clone()))
+        .or(get_rosters(controller.clone()))
+        .or(set_roster(controller.clone()))
+        .or(import_roster(controller.clone()))
+        .or(substitution_routes(controller.clone()))
+        .or(card_routes(controller.clone()))
+        .or(display_routes(controller.clone()))
+        .or(record_result(controller.clone(), league.clone()))
+        .or(finish_match(pitch_id.clone(), controller.clone(), archive, league))
+        .or(match_report(pitch_id.clone(), controller.clone()))
+        .or(list_profiles(controller.clone()))
+        .or(get_profile(controller.clone()))
+        .or(set_profile(controller.clone()))
+        .or(discover_cards(controller.clone()))
+        .or(use_discovered_card(pitch_id, controller.clone()))
+        .or(get_config(controller.clone()))
+        .or(update_config(controller.clone()))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/pitches
+fn list_pitches(
+    pitches: Pitches,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("pitches")
+        .and(warp::get())
+        .and_then(move || {
+            let pitches = pitches.clone();
+            async move {
+                json_reply(ApiResponse::success(pitches.summaries().await))
+            }
+        })
+}
+
+/// Team registry routes, shared by every pitch
+fn team_registry_routes(teams: Arc<TeamRegistry>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    list_teams(teams.clone())
+        .or(create_team(teams.clone()))
+        .or(update_team(teams.clone()))
+        .or(delete_team(teams))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/teams/registry
+fn list_teams(
+    teams: Arc<TeamRegistry>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("teams" / "registry")
+        .and(warp::get())
+        .and_then(move || {
+            let teams = teams.clone();
+            async move {
+                json_reply(ApiResponse::success(teams.list().await))
+            }
+        })
+}
+
+/// POST /api/teams/registry
+fn create_team(
+    teams: Arc<TeamRegistry>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("teams" / "registry")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |details: TeamDetails| {
+            let teams = teams.clone();
+            async move {
+                match teams.create(details).await {
+                    Ok(team) => {
+                        info!("Added team '{}'", team.id);
+                        json_reply(ApiResponse::success(team))
+                    }
+                    Err(e) => {
+                        error!("Failed to add team: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// PUT /api/teams/registry/{id}
+fn update_team(
+    teams: Arc<TeamRegistry>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("teams" / "registry" / String)
+        .and(warp::put())
+        .and(warp::body::json())
+        .and_then(move |id: String, details: TeamDetails| {
+            let teams = teams.clone();
+            async move {
+                match teams.update(&id, details).await {
+                    Ok(team) => {
+                        info!("Updated team '{}'", team.id);
+                        json_reply(ApiResponse::success(team))
+                    }
+                    Err(e) => {
+                        error!("Failed to update team '{}': {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// DELETE /api/teams/registry/{id}
+fn delete_team(
+    teams: Arc<TeamRegistry>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("teams" / "registry" / String)
+        .and(warp::delete())
+        .and_then(move |id: String| {
+            let teams = teams.clone();
+            async move {
+                match teams.delete(&id).await {
+                    Ok(_) => {
+                        info!("Deleted team '{}'", id);
+                        json_reply(ApiResponse::success("Team deleted".to_string()))
+                    }
+                    Err(e) => {
+                        error!("Failed to delete team '{}': {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// League table routes, shared by every pitch
+fn league_routes(league: Arc<LeagueTable>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    league_table(league.clone())
+        .or(list_results(league.clone()))
+        .or(delete_result(league))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/league/table
+fn league_table(
+    league: Arc<LeagueTable>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("league" / "table")
+        .and(warp::get())
+        .and(warp::query::<TableQuery>())
+        .and_then(move |query: TableQuery| {
+            let league = league.clone();
+            async move {
+                json_reply(ApiResponse::success(league.table(query.competition.as_deref()).await))
+            }
+        })
+}
+
+/// GET /api/league/results
+fn list_results(
+    league: Arc<LeagueTable>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("league" / "results")
+        .and(warp::get())
+        .and_then(move || {
+            let league = league.clone();
+            async move {
+                json_reply(ApiResponse::success(league.results().await))
+            }
+        })
+}
+
+/// DELETE /api/league/results/{id}
+fn delete_result(
+    league: Arc<LeagueTable>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("league" / "results" / String)
+        .and(warp::delete())
+        .and_then(move |id: String| {
+            let league = league.clone();
+            async move {
+                match league.delete(&id).await {
+                    Ok(_) => {
+                        info!("Deleted result '{}'", id);
+                        json_reply(ApiResponse::success("Result deleted".to_string()))
+                    }
+                    Err(e) => {
+                        error!("Failed to delete result '{}': {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// Match archive routes, shared by every pitch
+fn archive_routes(archive: Arc<MatchArchive>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    list_matches(archive.clone())
+        .or(export_matches(archive.clone()))
+        .or(get_match(archive.clone()))
+        .or(export_match(archive.clone()))
+        .or(archived_match_report(archive))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/matches
+fn list_matches(
+    archive: Arc<MatchArchive>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("matches")
+        .and(warp::get())
+        .and_then(move || {
+            let archive = archive.clone();
+            async move {
+                let summaries: Vec<_> = archive.list().await.iter().map(ArchivedMatch::summary).collect();
+                json_reply(ApiResponse::success(summaries))
+            }
+        })
+}
+
+/// GET /api/matches/export - every archived match as a download
+fn export_matches(
+    archive: Arc<MatchArchive>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("matches" / "export")
+        .and(warp::get())
+        .and(warp::query::<ExportQuery>())
+        .and_then(move |query: ExportQuery| {
+            let archive = archive.clone();
+            async move {
+                let matches = archive.list().await;
+                let body = match query.format {
+                    ExportFormat::Json => serde_json::to_string_pretty(&matches).map_err(anyhow::Error::from),
+                    ExportFormat::Csv => archive::matches_csv(&matches),
+                };
+                Ok::<_, Infallible>(download(body, query.format, "matches"))
+            }
+        })
+}
+
+/// GET /api/matches/{id}
+fn get_match(
+    archive: Arc<MatchArchive>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("matches" / String)
+        .and(warp::get())
+        .and_then(move |id: String| {
+            let archive = archive.clone();
+            async move {
+                match archive.get(&id).await {
+                    Some(archived) => json_reply(ApiResponse::success(archived)),
+                    None => json_reply(ApiResponse::<String>::error(format!("No match with ID '{}'", id))),
+                }
+            }
+        })
+}
+
+/// GET /api/matches/{id}/export - a match with its timeline as a download
+fn export_match(
+    archive: Arc<MatchArchive>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("matches" / String / "export")
+        .and(warp::get())
+        .and(warp::query::<ExportQuery>())
+        .and_then(move |id: String, query: ExportQuery| {
+            let archive = archive.clone();
+            async move {
+                let Some(archived) = archive.get(&id).await else {
+                    let reply: Box<dyn Reply> = Box::new(warp::reply::json(
+                        &ApiResponse::<String>::error(format!("No match with ID '{}'", id))));
+                    return Ok::<_, Infallible>(reply);
+                };
+                let body = match query.format {
+                    ExportFormat::Json => serde_json::to_string_pretty(&archived).map_err(anyhow::Error::from),
+                    ExportFormat::Csv => archive::timeline_csv(&archived),
+                };
+                Ok(download(body, query.format, &id))
+            }
+        })
+}
+
+/// GET /api/matches/{id}/report - printable HTML report
+fn archived_match_report(
+    archive: Arc<MatchArchive>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("matches" / String / "report")
+        .and(warp::get())
+        .and_then(move |id: String| {
+            let archive = archive.clone();
+            async move {
+                let reply: Box<dyn Reply> = match archive.get(&id).await {
+                    Some(archived) => Box::new(warp::reply::html(report::match_report(&archived))),
+                    None => Box::new(warp::reply::json(&ApiResponse::<String>::error(format!("No match with ID '{}'", id)))),
+                };
+                Ok::<_, Infallible>(reply)
+            }
+        })
+}
+
+/// An export as a file to save, or the error that stopped it
+fn download(body: anyhow::Result<String>, format: ExportFormat, name: &str) -> Box<dyn Reply> {
+    let (content_type, extension) = match format {
+        ExportFormat::Json => ("application/json", "json"),
+        ExportFormat::Csv => ("text/csv", "csv"),
+    };
+    match body {
+        Ok(body) => Box::new(warp::reply::with_header(
+            warp::reply::with_header(body, "content-type", content_type),
+            "content-disposition",
+            format!("attachment; filename=\"{}.{}\"", name, extension),
+        )),
+        Err(e) => {
+            error!("Failed to export {}: {}", name, e);
+            Box::new(warp::reply::json(&ApiResponse::<String>::error(e.to_string())))
+        }
+    }
+}
+
+/// Fixture routes, shared by every pitch
+fn fixture_routes(fixtures: Arc<FixtureStore>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    list_fixtures(fixtures.clone())
+        .or(import_fixtures(fixtures.clone()))
+        .or(delete_fixture(fixtures))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/fixtures
+fn list_fixtures(
+    fixtures: Arc<FixtureStore>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("fixtures")
+        .and(warp::get())
+        .and(warp::query::<FixturesQuery>())
+        .and_then(move |query: FixturesQuery| {
+            let fixtures = fixtures.clone();
+            async move {
+                let list = if query.all {
+                    fixtures.list().await
+                } else {
+                    let today = chrono::Local::now().date_naive().and_hms_opt(0, 0, 0).unwrap();
+                    fixtures.upcoming(today).await
+                };
+                json_reply(ApiResponse::success(list))
+            }
+        })
+}
+
+/// POST /api/fixtures/import
+fn import_fixtures(
+    fixtures: Arc<FixtureStore>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("fixtures" / "import")
+        .and(warp::post())
+        .and(warp::query::<ImportQuery>())
+        .and(warp::body::content_length_limit(1024 * 1024))
+        .and(warp::body::bytes())
+        .and_then(move |query: ImportQuery, body: bytes::Bytes| {
+            let fixtures = fixtures.clone();
+            async move {
+                let result = async {
+                    let content = std::str::from_utf8(&body)?;
+                    let format = query.format.unwrap_or_else(|| ImportFormat::detect(content));
+                    fixtures.import(fixtures::parse(content, format)?).await
+                }.await;
+                match result {
+                    Ok(imported) => {
+                        info!("Imported {} fixture(s)", imported);
+                        json_reply(ApiResponse::success(ImportResult { imported }))
+                    }
+                    Err(e) => {
+                        error!("Failed to import fixtures: {:#}", e);
+                        json_reply(ApiResponse::<String>::error(format!("{:#}", e)))
+                    }
+                }
+            }
+        })
+}
+
+/// DELETE /api/fixtures/{id}
+fn delete_fixture(
+    fixtures: Arc<FixtureStore>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("fixtures" / String)
+        .and(warp::delete())
+        .and_then(move |id: String| {
+            let fixtures = fixtures.clone();
+            async move {
+                match fixtures.delete(&id).await {
+                    Ok(_) => {
+                        info!("Deleted fixture '{}'", id);
+                        json_reply(ApiResponse::success("Fixture deleted".to_string()))
+                    }
+                    Err(e) => {
+                        error!("Failed to delete fixture '{}': {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// GET /api/status
+fn get_status(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("status")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                let state = controller.get_state().await;
+                let connected = controller.is_connected().await;
+                let mut response_state = state;
+                response_state.connected = connected;
+                let boards = controller.board_statuses().await;
+                json_reply(ApiResponse::success(StatusResponse { state: response_state, boards }))
+            }
+        })
+}
+
+/// POST /api/teams
+fn set_teams(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("teams")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |update: TeamUpdate| {
+            let controller = controller.clone();
+            async move {
+                let result = controller.set_teams_with_short_names(
+                    update.home_team,
+                    update.home_short_name.filter(|name| !name.is_empty()),
+                    update.away_team,
+                    update.away_short_name.filter(|name| !name.is_empty()),
+                ).await;
+                match result {
+                    Ok(_) => {
+                        info!("Teams updated successfully");
+                        json_reply(ApiResponse::success("Teams updated".to_string()))
+                    }
+                    Err(e) => {
+                        error!("Failed to update teams: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/teams/select
+fn select_teams(
+    controller: Arc<ScoreboardController>,
+    teams: Arc<TeamRegistry>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("teams" / "select")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |selection: TeamSelection| {
+            let controller = controller.clone();
+            let teams = teams.clone();
+            async move {
+                let (Some(home), Some(away)) = (teams.get(&selection.home_id).await, teams.get(&selection.away_id).await) else {
+                    return json_reply(ApiResponse::<String>::error("Unknown team ID".to_string()));
+                };
+                match controller.select_teams(&home, &away).await {
+                    Ok(_) => {
+                        info!("Selected teams '{}' and '{}'", home.id, away.id);
+                        json_reply(ApiResponse::success("Teams selected".to_string()))
+                    }
+                    Err(e) => {
+                        error!("Failed to select teams: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// GET /api/roster
+fn get_rosters(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("roster")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                json_reply(ApiResponse::success(controller.rosters().await))
+            }
+        })
+}
+
+/// PUT /api/roster/{team}
+fn set_roster(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("roster" / String)
+        .and(warp::put())
+        .and(warp::body::json())
+        .and_then(move |team: String, players: Vec<Player>| {
+            let controller = controller.clone();
+            async move {
+                let count = players.len();
+                match controller.set_roster(&team, players).await {
+                    Ok(_) => {
+                        info!("Set {} squad of {} player(s)", team, count);
+                        json_reply(ApiResponse::success(controller.rosters().await))
+                    }
+                    Err(e) => {
+                        error!("Failed to set {} squad: {}", team, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/roster/{team}/import
+fn import_roster(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("roster" / String / "import")
+        .and(warp::post())
+        .and(warp::body::content_length_limit(64 * 1024))
+        .and(warp::body::bytes())
+        .and_then(move |team: String, body: bytes::Bytes| {
+            let controller = controller.clone();
+            async move {
+                let result = async {
+                    let players = roster::parse_csv(std::str::from_utf8(&body)?)?;
+                    let count = players.len();
+                    controller.set_roster(&team, players).await?;
+                    anyhow::Ok(count)
+                }.await;
+                match result {
+                    Ok(imported) => {
+                        info!("Imported {} squad of {} player(s)", team, imported);
+                        json_reply(ApiResponse::success(ImportResult { imported }))
+                    }
+                    Err(e) => {
+                        error!("Failed to import {} squad: {:#}", team, e);
+                        json_reply(ApiResponse::<String>::error(format!("{:#}", e)))
+                    }
+                }
+            }
+        })
+}
+
+/// Discipline routes
+fn card_routes(controller: Arc<ScoreboardController>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    get_discipline(controller.clone())
+        .or(give_card(controller.clone()))
+        .or(delete_card(controller))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/cards
+fn get_discipline(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("cards")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                json_reply(ApiResponse::success(controller.discipline().await))
+            }
+        })
+}
+
+/// POST /api/cards
+fn give_card(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("cards")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |request: CardRequest| {
+            let controller = controller.clone();
+            async move {
+                match controller.give_card(&request.team, &request.card, request.player).await {
+                    Ok(card) => json_reply(ApiResponse::success(card)),
+                    Err(e) => {
+                        error!("Failed to record card: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// DELETE /api/cards/{id}
+fn delete_card(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("cards" / u32)
+        .and(warp::delete())
+        .and_then(move |id: u32| {
+            let controller = controller.clone();
+            async move {
+                match controller.remove_card(id).await {
+                    Ok(card) => {
+                        info!("Removed card {}", id);
+                        json_reply(ApiResponse::success(card))
+                    }
+                    Err(e) => {
+                        error!("Failed to remove card {}: {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// Substitution and temporary replacement routes
+fn substitution_routes(controller: Arc<ScoreboardController>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    list_substitutions(controller.clone())
+        .or(substitute(controller.clone()))
+        .or(end_replacement(controller.clone()))
+        .or(delete_substitution(controller))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/substitutions
+fn list_substitutions(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("substitutions")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                json_reply(ApiResponse::success(controller.substitutions().await))
+            }
+        })
+}
+
+/// POST /api/substitutions
+fn substitute(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("substitutions")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |request: SubstitutionRequest| {
+            let controller = controller.clone();
+            async move {
+                match controller.substitute(&request.team, request.kind, request.off, request.on).await {
+                    Ok(substitution) => json_reply(ApiResponse::success(substitution)),
+                    Err(e) => {
+                        error!("Failed to record substitution: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/substitutions/{id}/return and /api/substitutions/{id}/permanent
+fn end_replacement(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let returned = warp::path!("substitutions" / u32 / "return").map(|id| (id, true));
+    let permanent = warp::path!("substitutions" / u32 / "permanent").map(|id| (id, false));
+    returned.or(permanent)
+        .unify()
+        .and(warp::post())
+        .and_then(move |(id, returned): (u32, bool)| {
+            let controller = controller.clone();
+            async move {
+                match controller.end_replacement(id, returned).await {
+                    Ok(substitution) => json_reply(ApiResponse::success(substitution)),
+                    Err(e) => {
+                        error!("Failed to end replacement {}: {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// DELETE /api/substitutions/{id}
+fn delete_substitution(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("substitutions" / u32)
+        .and(warp::delete())
+        .and_then(move |id: u32| {
+            let controller = controller.clone();
+            async move {
+                match controller.remove_substitution(id).await {
+                    Ok(substitution) => {
+                        info!("Removed substitution {}", id);
+                        json_reply(ApiResponse::success(substitution))
+                    }
+                    Err(e) => {
+                        error!("Failed to remove substitution {}: {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/league/results
+fn record_result(
+    controller: Arc<ScoreboardController>,
+    league: Arc<LeagueTable>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("league" / "results")
+        .and(warp::post())
+        .and_then(move || {
+            let controller = controller.clone();
+            let league = league.clone();
+            async move {
+                let result = controller.result().await;
+                match league.record(result.clone()).await {
+                    Ok(_) => {
+                        info!("Recorded result {} {}-{} {}", result.home, result.home_score, result.away_score, result.away);
+                        json_reply(ApiResponse::success(result))
+                    }
+                    Err(e) => {
+                        error!("Failed to record result: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/match/finish - archive the match and record its result in
+/// the league table
+fn finish_match(
+    pitch_id: String,
+    controller: Arc<ScoreboardController>,
+    archive: Arc<MatchArchive>,
+    league: Arc<LeagueTable>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("match" / "finish")
+        .and(warp::post())
+        .and_then(move || {
+            let pitch_id = pitch_id.clone();
+            let controller = controller.clone();
+            let archive = archive.clone();
+            let league = league.clone();
+            async move {
+                let archived = controller.finish_match(&pitch_id).await;
+                let result = async {
+                    archive.store(archived.clone()).await?;
+                    league.record(archived.result()).await
+                }.await;
+                match result {
+                    Ok(_) => {
+                        info!("Finished match '{}': {} {}-{} {}", archived.id,
+                            archived.home.name, archived.home.score, archived.away.score, archived.away.name);
+                        json_reply(ApiResponse::success(archived.summary()))
+                    }
+                    Err(e) => {
+                        error!("Failed to archive match '{}': {}", archived.id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// GET /api/match/report - printable HTML report of the match so far
+fn match_report(
+    pitch_id: String,
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("match" / "report")
+        .and(warp::get())
+        .and_then(move || {
+            let pitch_id = pitch_id.clone();
+            let controller = controller.clone();
+            async move {
+                let snapshot = controller.snapshot(&pitch_id).await;
+                Ok::<_, Infallible>(warp::reply::html(report::match_report(&snapshot)))
+            }
+        })
+}
+
+/// POST /api/fixtures/{id}/setup
+fn setup_fixture(
+    controller: Arc<ScoreboardController>,
+    teams: Arc<TeamRegistry>,
+    fixtures: Arc<FixtureStore>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("fixtures" / String / "setup")
+        .and(warp::post())
+        .and_then(move |id: String| {
+            let controller = controller.clone();
+            let teams = teams.clone();
+            let fixtures = fixtures.clone();
+            async move {
+                let Some(fixture) = fixtures.get(&id).await else {
+                    return json_reply(ApiResponse::<String>::error(format!("No fixture with ID '{}'", id)));
+                };
+                let home = teams.find_by_name(&fixture.home).await;
+                let away = teams.find_by_name(&fixture.away).await;
+                match controller.setup_fixture(&fixture, home.as_ref(), away.as_ref()).await {
+                    Ok(_) => {
+                        info!("Set up match from fixture '{}'", id);
+                        json_reply(ApiResponse::success(controller.get_state().await))
+                    }
+                    Err(e) => {
+                        error!("Failed to set up fixture '{}': {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/score/action
+fn score_action(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("score" / "action")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |request: ScoreActionRequest| {
+            let controller = controller.clone();
+            async move {
+                if request.undo {
+                    return match controller.undo_action(&request.team, &request.action).await {
+                        Ok(points) => {
+                            info!("{} taken back for team: {}", request.action, request.team);
+                            json_reply(ApiResponse::success(format!("{} (-{}) for {}", request.action, points, request.team)))
+                        }
+                        Err(e) => {
+                            error!("Failed to take back {}: {}", request.action, e);
+                            json_reply(ApiResponse::<String>::error(e.to_string()))
+                        }
+                    };
+                }
 
-    pub fn error(message: String) -> Self {
-        Self {
-            success: false,
-            data: None,
-            error: Some(message),
-        }
-    }
+                match controller.score_action_by(&request.team, &request.action, request.player).await {
+                    Ok(event) => {
+                        info!("{} scored for team: {}", request.action, request.team);
+                        json_reply(ApiResponse::success(event))
+                    }
+                    Err(e) => {
+                        error!("Failed to score {}: {}", request.action, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
 }
 
-// Helper function to create a JSON reply with proper type annotations
-fn json_reply<T: serde::Serialize>(response: ApiResponse<T>) -> Result<warp::reply::Json, Infallible> {
-    Ok(warp::reply::json(&response))
+/// POST /api/score/undo
+fn undo_score(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("score" / "undo")
+        .and(warp::post())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                match controller.undo_last().await {
+                    Ok(event) => {
+                        info!("Took back {} for team: {:?}", event.action, event.team);
+                        json_reply(ApiResponse::success(event))
+                    }
+                    Err(e) => {
+                        error!("Failed to undo score: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
 }
 
-/// Create the web server routes
-pub fn create_routes(
+/// GET /api/score/events
+fn score_events(
     controller: Arc<ScoreboardController>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    let cors = warp::cors()
-        .allow_any_origin()
-        .allow_headers(vec!["content-type"])
-        .allow_methods(vec!["GET", "POST", "PUT", "DELETE"]);
-
-    let static_files = warp::path("static")
-        .and(warp::fs::dir("static"));
-
-    let index = warp::path::end()
+    warp::path!("score" / "events")
         .and(warp::get())
-        .and(warp::fs::file("static/index.html"));
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                json_reply(ApiResponse::success(controller.timeline().await))
+            }
+        })
+}
 
-    let config_page = warp::path("config")
+/// GET /api/profiles
+fn list_profiles(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("profiles")
         .and(warp::get())
-        .and(warp::fs::file("static/config.html"));
-
-    let api_routes = warp::path("api").and(
-        get_status(controller.clone())
-            .or(set_teams(controller.clone()))
-            .or(set_scores(controller.clone()))
-            .or(increment_home_score(controller.clone()))
-            .or(increment_away_score(controller.clone()))
-            .or(reset_scores(controller.clone()))
-            .or(set_timer(controller.clone()))
-            .or(start_timer(controller.clone()))
-            .or(stop_timer(controller.clone()))
-            .or(reset_timer(controller.clone()))
-            .or(add_try(controller.clone()))
-            .or(remove_try(controller.clone()))
-            .or(add_conversion(controller.clone()))
-            .or(add_penalty(controller.clone()))
-            .or(get_config(controller.clone()))
-            .or(update_config(controller.clone()))
-    );
-
-    index
-        .or(config_page)
-        .or(static_files)
-        .or(api_routes)
-        .with(cors)
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                json_reply(ApiResponse::success(controller.profiles()))
+            }
+        })
 }
 
-/// GET /api/status
-fn get_status(
+/// GET /api/profile
+fn get_profile(
     controller: Arc<ScoreboardController>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::path!("status")
+    warp::path!("profile")
         .and(warp::get())
         .and_then(move || {
             let controller = controller.clone();
             async move {
-                let state = controller.get_state().await;
-                let connected = controller.is_connected().await;
-                let mut response_state = state;
-                response_state.connected = connected;
-                json_reply(ApiResponse::success(response_state))
+                json_reply(ApiResponse::success(controller.profile().await))
             }
         })
 }
 
-/// POST /api/teams
-fn set_teams(
+/// POST /api/profile
+fn set_profile(
     controller: Arc<ScoreboardController>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::path!("teams")
+    warp::path!("profile")
         .and(warp::post())
         .and(warp::body::json())
-        .and_then(move |update: TeamUpdate| {
+        .and_then(move |selection: ProfileSelection| {
             let controller = controller.clone();
             async move {
-                match controller.set_teams(update.home_team, update.away_team).await {
+                match controller.set_profile(&selection.name).await {
                     Ok(_) => {
-                        info!("Teams updated successfully");
-                        json_reply(ApiResponse::success("Teams updated".to_string()))
+                        info!("Rule profile set to {}", selection.name);
+                        json_reply(ApiResponse::success(controller.profile().await))
                     }
                     Err(e) => {
-                        error!("Failed to update teams: {}", e);
+                        error!("Failed to set rule profile: {}", e);
                         json_reply(ApiResponse::<String>::error(e.to_string()))
                     }
                 }
@@ -342,23 +1455,70 @@ fn reset_timer(
                 }
             }
         })
-}/// POST /api/rugby/try
-fn add_try(
+}
+
+/// POST /api/period/end
+fn end_period(
     controller: Arc<ScoreboardController>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::path!("rugby" / "try")
+    warp::path!("period" / "end")
         .and(warp::post())
-        .and(warp::body::json())
-        .and_then(move |team_action: TeamAction| {
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                match controller.end_period().await {
+                    Ok(state) => {
+                        info!("Period {} ended at {}-{}", state.period, state.home_score, state.away_score);
+                        json_reply(ApiResponse::success(state))
+                    }
+                    Err(e) => {
+                        error!("Failed to end period: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/period/start
+fn start_period(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("period" / "start")
+        .and(warp::post())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                match controller.start_period().await {
+                    Ok(state) => {
+                        info!("Period {} started", state.period);
+                        json_reply(ApiResponse::success(state))
+                    }
+                    Err(e) => {
+                        error!("Failed to start period: {}", e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/shot-clock/start
+fn start_shot_clock(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("shot-clock" / "start")
+        .and(warp::post())
+        .and_then(move || {
             let controller = controller.clone();
             async move {
-                match controller.add_try(&team_action.team).await {
+                match controller.start_shot_clock().await {
                     Ok(_) => {
-                        info!("Try added for team: {}", team_action.team);
-                        json_reply(ApiResponse::success(format!("Try added for {}", team_action.team)))
+                        info!("Shot clock started");
+                        json_reply(ApiResponse::success("Shot clock started".to_string()))
                     }
                     Err(e) => {
-                        error!("Failed to add try: {}", e);
+                        error!("Failed to start shot clock: {}", e);
                         json_reply(ApiResponse::<String>::error(e.to_string()))
                     }
                 }
@@ -366,23 +1526,22 @@ fn add_try(
         })
 }
 
-/// DELETE /api/rugby/try
-fn remove_try(
+/// POST /api/shot-clock/cancel
+fn cancel_shot_clock(
     controller: Arc<ScoreboardController>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::path!("rugby" / "try")
-        .and(warp::delete())
-        .and(warp::body::json())
-        .and_then(move |team_action: TeamAction| {
+    warp::path!("shot-clock" / "cancel")
+        .and(warp::post())
+        .and_then(move || {
             let controller = controller.clone();
             async move {
-                match controller.remove_try(&team_action.team).await {
+                match controller.cancel_shot_clock().await {
                     Ok(_) => {
-                        info!("Try removed for team: {}", team_action.team);
-                        json_reply(ApiResponse::success(format!("Try removed for {}", team_action.team)))
+                        info!("Shot clock stopped");
+                        json_reply(ApiResponse::success("Shot clock stopped".to_string()))
                     }
                     Err(e) => {
-                        error!("Failed to remove try: {}", e);
+                        error!("Failed to stop shot clock: {}", e);
                         json_reply(ApiResponse::<String>::error(e.to_string()))
                     }
                 }
@@ -390,23 +1549,120 @@ fn remove_try(
         })
 }
 
-/// POST /api/rugby/conversion
-fn add_conversion(
+/// GET /api/discover
+fn discover_cards(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("discover")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                let port = discovery::port_from_address(&controller.config().scoreboard.address);
+                match discovery::discover(port, Duration::from_secs(3)).await {
+                    Ok(cards) => json_reply(ApiResponse::success(cards)),
+                    Err(e) => {
+                        error!("Failed to run discovery: {}", e);
+                        json_reply(ApiResponse::<Vec<DiscoveredCard>>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// POST /api/discover/use
+fn use_discovered_card(
+    pitch_id: String,
     controller: Arc<ScoreboardController>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::path!("rugby" / "conversion")
+    warp::path!("discover" / "use")
         .and(warp::post())
         .and(warp::body::json())
-        .and_then(move |team_action: TeamAction| {
+        .and_then(move |selection: CardSelection| {
             let controller = controller.clone();
+            let pitch_id = pitch_id.clone();
             async move {
-                match controller.add_conversion(&team_action.team).await {
+                let board = selection.board.as_deref();
+                let saved = Config::load().and_then(|mut config| {
+                    config.set_board_target(Some(&pitch_id), board, selection.address.clone(), selection.card_id)?;
+                    config.save()
+                });
+                if let Err(e) = saved {
+                    error!("Failed to save scoreboard config: {}", e);
+                    return json_reply(ApiResponse::<String>::error(e.to_string()));
+                }
+
+                match controller.set_target(board, selection.address.clone(), selection.card_id).await {
                     Ok(_) => {
-                        info!("Conversion added for team: {}", team_action.team);
-                        json_reply(ApiResponse::success(format!("Conversion added for {}", team_action.team)))
+                        info!("Using card {} at {}", selection.card_id, selection.address);
+                        json_reply(ApiResponse::success(format!("Using card {} at {}", selection.card_id, selection.address)))
+                    }
+                    Err(e) => {
+                        error!("Saved card {} at {} but failed to connect: {}", selection.card_id, selection.address, e);
+                        json_reply(ApiResponse::<String>::error(format!("Configuration saved, but connection failed: {}", e)))
+                    }
+                }
+            }
+        })
+}
+
+/// What the board shows besides the match: messages, sponsors and previews
+fn display_routes(controller: Arc<ScoreboardController>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    message_routes(controller.clone())
+        .or(sponsor_routes(controller.clone()))
+        .or(display_preview(controller.clone()))
+        .or(display_live(controller))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// Board message routes
+fn message_routes(controller: Arc<ScoreboardController>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    list_messages(controller.clone())
+        .or(queue_message(controller.clone()))
+        .or(queue_image(controller.clone()))
+        .or(cancel_message(controller))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/display/messages
+fn list_messages(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("display" / "messages")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                json_reply(ApiResponse::success(controller.messages().await))
+            }
+        })
+}
+
+/// POST /api/display/message
+fn queue_message(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("display" / "message")
+        .and(warp::post())
+        .and(warp::body::json())
+        .and_then(move |request: MessageRequest| {
+            let controller = controller.clone();
+            async move {
+                let result = async {
+                    let window = message_window(request.window.as_deref(), request.fullscreen)?;
+                    let effect = message_effect(request.effect.as_deref())?;
+                    let color = request.color.unwrap_or(Color::YELLOW);
+                    controller.queue_message(request.text, request.duration, window, effect, color).await
+                }.await;
+                match result {
+                    Ok(message) => {
+                        info!("Queued message {} '{}' for {}s", message.id, message.text, message.seconds);
+                        json_reply(ApiResponse::success(message))
                     }
                     Err(e) => {
-                        error!("Failed to add conversion: {}", e);
+                        error!("Failed to queue message: {}", e);
                         json_reply(ApiResponse::<String>::error(e.to_string()))
                     }
                 }
@@ -414,23 +1670,118 @@ fn add_conversion(
         })
 }
 
-/// POST /api/rugby/penalty
-fn add_penalty(
+/// POST /api/display/image?window=home_name&duration=10 - a PNG or BMP body
+fn queue_image(
     controller: Arc<ScoreboardController>,
 ) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
-    warp::path!("rugby" / "penalty")
+    warp::path!("display" / "image")
         .and(warp::post())
+        .and(warp::query::<ImageQuery>())
+        .and(warp::body::content_length_limit(4 * 1024 * 1024))
+        .and(warp::body::bytes())
+        .and_then(move |query: ImageQuery, body: bytes::Bytes| {
+            let controller = controller.clone();
+            async move {
+                let result = async {
+                    let window = message_window(query.window.as_deref(), query.fullscreen)?;
+                    let effect = message_effect(query.effect.as_deref())?;
+                    let image = Image::decode(&body)?;
+                    controller.queue_image(query.name, image, query.duration, window, effect).await
+                }.await;
+                match result {
+                    Ok(message) => {
+                        info!("Queued image {} '{}' for {}s", message.id, message.text, message.seconds);
+                        json_reply(ApiResponse::success(message))
+                    }
+                    Err(e) => {
+                        error!("Failed to queue image: {:#}", e);
+                        json_reply(ApiResponse::<String>::error(format!("{:#}", e)))
+                    }
+                }
+            }
+        })
+}
+
+/// Window a message replaces by name, none for the whole board
+fn message_window(name: Option<&str>, fullscreen: bool) -> anyhow::Result<Option<u8>> {
+    match (name, fullscreen) {
+        (Some(_), true) => anyhow::bail!("Give either a window or fullscreen, not both"),
+        (Some(name), false) => Ok(Some(windows::by_name(name).ok_or_else(|| anyhow::anyhow!("Unknown window: {}", name))?)),
+        (None, _) => Ok(None),
+    }
+}
+
+/// Display effect by name, instant when none is given
+fn message_effect(name: Option<&str>) -> anyhow::Result<u8> {
+    match name {
+        Some(name) => effects::by_name(name).ok_or_else(|| anyhow::anyhow!("Unknown effect: {}", name)),
+        None => Ok(effects::INSTANT),
+    }
+}
+
+/// DELETE /api/display/messages/{id}
+fn cancel_message(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("display" / "messages" / u32)
+        .and(warp::delete())
+        .and_then(move |id: u32| {
+            let controller = controller.clone();
+            async move {
+                match controller.cancel_message(id).await {
+                    Ok(message) => {
+                        info!("Cancelled message {}", id);
+                        json_reply(ApiResponse::success(message))
+                    }
+                    Err(e) => {
+                        error!("Failed to cancel message {}: {}", id, e);
+                        json_reply(ApiResponse::<String>::error(e.to_string()))
+                    }
+                }
+            }
+        })
+}
+
+/// Sponsor playlist routes
+fn sponsor_routes(controller: Arc<ScoreboardController>) -> BoxedFilter<(Box<dyn Reply>,)> {
+    get_sponsors(controller.clone())
+        .or(set_sponsors(controller.clone()))
+        .or(sponsor_log(controller))
+        .map(|reply| Box::new(reply) as Box<dyn Reply>)
+        .boxed()
+}
+
+/// GET /api/sponsors
+fn get_sponsors(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("sponsors")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                json_reply(ApiResponse::success(controller.sponsors().await))
+            }
+        })
+}
+
+/// PUT /api/sponsors
+fn set_sponsors(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("sponsors")
+        .and(warp::put())
         .and(warp::body::json())
-        .and_then(move |team_action: TeamAction| {
+        .and_then(move |config: SponsorConfig| {
             let controller = controller.clone();
             async move {
-                match controller.add_penalty(&team_action.team).await {
-                    Ok(_) => {
-                        info!("Penalty added for team: {}", team_action.team);
-                        json_reply(ApiResponse::success(format!("Penalty added for {}", team_action.team)))
+                match controller.set_sponsors(config).await {
+                    Ok(status) => {
+                        info!("Sponsor playlist set to {} item(s)", status.playlist.len());
+                        json_reply(ApiResponse::success(status))
                     }
                     Err(e) => {
-                        error!("Failed to add penalty: {}", e);
+                        error!("Failed to set the sponsor playlist: {}", e);
                         json_reply(ApiResponse::<String>::error(e.to_string()))
                     }
                 }
@@ -438,6 +1789,75 @@ fn add_penalty(
         })
 }
 
+/// GET /api/sponsors/log?format=json|csv - every showing as a download
+fn sponsor_log(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("sponsors" / "log")
+        .and(warp::get())
+        .and(warp::query::<ExportQuery>())
+        .and_then(move |query: ExportQuery| {
+            let controller = controller.clone();
+            async move {
+                let log = controller.sponsor_log().await;
+                let body = match query.format {
+                    ExportFormat::Json => serde_json::to_string_pretty(&log).map_err(anyhow::Error::from),
+                    ExportFormat::Csv => sponsors::log_csv(&log),
+                };
+                Ok::<_, Infallible>(download(body, query.format, "sponsors"))
+            }
+        })
+}
+
+/// GET /api/display/preview?format=png|svg&scale=N
+fn display_preview(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("display" / "preview")
+        .and(warp::get())
+        .and(warp::query::<PreviewQuery>())
+        .and_then(move |query: PreviewQuery| {
+            let controller = controller.clone();
+            async move {
+                let frame = controller.preview().await;
+                let reply: Box<dyn Reply> = match query.format.as_deref() {
+                    Some("png") => match frame.to_png(query.scale.unwrap_or(1).min(16)) {
+                        Ok(png) => Box::new(warp::reply::with_header(png, "content-type", "image/png")),
+                        Err(e) => {
+                            error!("Failed to render preview: {}", e);
+                            Box::new(warp::reply::json(&ApiResponse::<String>::error(e.to_string())))
+                        }
+                    },
+                    Some("svg") | None => Box::new(warp::reply::with_header(frame.to_svg(), "content-type", "image/svg+xml")),
+                    Some(other) => Box::new(warp::reply::json(&ApiResponse::<String>::error(format!("Unknown format: {}", other)))),
+                };
+                Ok::<_, Infallible>(reply)
+            }
+        })
+}
+
+/// GET /api/display/live - server-sent `preview` events carrying a PNG
+/// data URL of the board, starting with what it shows now
+fn display_live(
+    controller: Arc<ScoreboardController>,
+) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    warp::path!("display" / "live")
+        .and(warp::get())
+        .and_then(move || {
+            let controller = controller.clone();
+            async move {
+                // Subscribe first so no update is missed while rendering the current one
+                let updates = BroadcastStream::new(controller.subscribe_previews())
+                    .filter_map(|preview| preview.ok());
+                let current = controller.preview_data_url().await.ok();
+                let events = tokio_stream::iter(current)
+                    .chain(updates)
+                    .map(|preview| Ok::<_, Infallible>(warp::sse::Event::default().event("preview").data(preview)));
+                Ok::<_, Infallible>(warp::sse::reply(warp::sse::keep_alive().stream(events)))
+            }
+        })
+}
+
 /// GET /api/config
 fn get_config(
     controller: Arc<ScoreboardController>,
@@ -445,7 +1865,7 @@ fn get_config(
     warp::path!("config")
         .and(warp::get())
         .and_then(move || {
-            let controller = controller.clone();
+            let _controller = controller.clone();
             async move {
                 // Get current config from controller
                 json_reply(ApiResponse::success("Configuration access not yet implemented".to_string()))
@@ -460,8 +1880,8 @@ fn update_config(
     warp::path!("config")
         .and(warp::post())
         .and(warp::body::json())
-        .and_then(move |config_update: ConfigUpdate| {
-            let controller = controller.clone();
+        .and_then(move |_config_update: ConfigUpdate| {
+            let _controller = controller.clone();
             async move {
                 // Update config through controller
                 json_reply(ApiResponse::success("Configuration update not yet implemented".to_string()))
@@ -0,0 +1,186 @@
+use std::fmt::Write;
+
+use crate::archive::ArchivedMatch;
+use crate::scoreboard::{Player, Side, SubstitutionStatus};
+
+/// A printable HTML report of a match for the league secretary and the
+/// referee
+pub fn match_report(archived: &ArchivedMatch) -> String {
+    let home = escape(&archived.home.name);
+    let away = escape(&archived.away.name);
+    // Only known when the break was started with the clock
+    let (half_time_home, half_time_away) = match archived.half_time {
+        Some(score) => (score.home.to_string(), score.away.to_string()),
+        None => ("-".to_string(), "-".to_string()),
+    };
+    let played_at = archived.played_at().format("%A %-d %B %Y, %H:%M");
+
+    let mut html = String::new();
+    let _ = write!(html, r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <title>Match Report - {home} v {away}</title>
+    <style>
+        body {{ font-family: Arial, sans-serif; margin: 20px; color: #000; }}
+        h1 {{ margin-bottom: 0; }}
+        .details {{ color: #444; margin-bottom: 20px; }}
+        table {{ width: 100%; border-collapse: collapse; margin-bottom: 20px; }}
+        th, td {{ border: 1px solid #999; padding: 4px 8px; text-align: left; }}
+        th {{ background-color: #eee; }}
+        td.number {{ text-align: center; width: 4em; }}
+        .signatures td {{ height: 3em; vertical-align: bottom; }}
+        @media print {{ .no-print {{ display: none; }} }}
+    </style>
+</head>
+<body>
+    <button class="no-print" onclick="window.print()">Print</button>
+    <h1>{home} v {away}</h1>
+    <div class="details">{competition}{played_at} &middot; {pitch}</div>
+
+    <table>
+        <tr><th>Team</th><th>Half time</th><th>Full time</th></tr>
+        <tr><td>{home}</td><td class="number">{half_time_home}</td><td class="number">{home_score}</td></tr>
+        <tr><td>{away}</td><td class="number">{half_time_away}</td><td class="number">{away_score}</td></tr>
+    </table>
+
+    <h2>Scoring</h2>
+"#,
+        competition = archived.competition.as_deref().map(|c| format!("{} &middot; ", escape(c))).unwrap_or_default(),
+        pitch = escape(&archived.pitch),
+        home_score = archived.home.score,
+        away_score = archived.away.score,
+    );
+
+    if archived.events.is_empty() {
+        html.push_str("    <p>No scores recorded.</p>\n");
+    } else {
+        html.push_str("    <table>\n        <tr><th>Min</th><th>Team</th><th>Score</th><th>Player</th><th>Points</th><th>Running score</th></tr>\n");
+        let (mut home_score, mut away_score) = (0, 0);
+        for event in &archived.events {
+            let team = match event.team {
+                Side::Home => {
+                    home_score += event.points;
+                    &home
+                }
+                Side::Away => {
+                    away_score += event.points;
+                    &away
+                }
+            };
+            let _ = writeln!(
+                html,
+                "        <tr><td class=\"number\">{}'</td><td>{}</td><td>{}</td><td>{}</td><td class=\"number\">{}</td><td>{} - {}</td></tr>",
+                u16::from(event.minute) + 1, team, escape(&event.label), event.player.as_ref().map(player).unwrap_or_default(),
+                event.points, home_score, away_score,
+            );
+        }
+        html.push_str("    </table>\n");
+    }
+
+    let home_scorers = archived.try_scorers(Side::Home);
+    let away_scorers = archived.try_scorers(Side::Away);
+    if !home_scorers.is_empty() || !away_scorers.is_empty() {
+        let _ = writeln!(html, "\n    <h2>Try scorers</h2>\n    <table>\n        <tr><th>{}</th><th>{}</th></tr>", home, away);
+        for row in 0..home_scorers.len().max(away_scorers.len()) {
+            let cell = |scorers: &[(Player, u16)]| scorers.get(row)
+                .map(|(scorer, tries)| if *tries > 1 { format!("{} ({})", player(scorer), tries) } else { player(scorer) })
+                .unwrap_or_default();
+            let _ = writeln!(html, "        <tr><td>{}</td><td>{}</td></tr>", cell(&home_scorers), cell(&away_scorers));
+        }
+        html.push_str("    </table>\n");
+    }
+
+    if !archived.substitutions.is_empty() {
+        html.push_str("\n    <h2>Replacements</h2>\n    <table>\n        <tr><th>Min</th><th>Team</th><th>Type</th><th>Off</th><th>On</th><th>Outcome</th></tr>\n");
+        for substitution in &archived.substitutions {
+            let team = match substitution.team {
+                Side::Home => &home,
+                Side::Away => &away,
+            };
+            let ended = substitution.ended_minute.map(|minute| format!(" ({}')", u16::from(minute) + 1)).unwrap_or_default();
+            let outcome = match substitution.status {
+                SubstitutionStatus::Permanent => format!("Permanent{}", ended),
+                SubstitutionStatus::Returned => format!("Returned{}", ended),
+                SubstitutionStatus::Active => "Off the field".to_string(),
+                SubstitutionStatus::Expired => "Did not return".to_string(),
+            };
+            let _ = writeln!(
+                html,
+                "        <tr><td class=\"number\">{}'</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                u16::from(substitution.minute) + 1, team, substitution.kind.label(), player(&substitution.off), player(&substitution.on), outcome,
+            );
+        }
+        html.push_str("    </table>\n");
+    }
+
+    if !archived.cards.is_empty() {
+        html.push_str("\n    <h2>Cards</h2>\n    <table>\n        <tr><th>Min</th><th>Team</th><th>Card</th><th>Player</th></tr>\n");
+        for card in &archived.cards {
+            let team = match card.team {
+                Side::Home => &home,
+                Side::Away => &away,
+            };
+            let _ = writeln!(
+                html,
+                "        <tr><td class=\"number\">{}'</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                u16::from(card.minute) + 1, team, card.label(), player(&card.player),
+            );
+        }
+        html.push_str("    </table>\n");
+    }
+
+    html.push_str(r#"
+    <table class="signatures">
+        <tr><th>Referee</th><th>Home captain</th><th>Away captain</th></tr>
+        <tr><td></td><td></td><td></td></tr>
+    </table>
+</body>
+</html>
+"#);
+    html
+}
+
+/// Shirt number and name, such as "11 John Smith"
+fn player(player: &Player) -> String {
+    escape(format!("{} {}", player.number, player.name).trim_end())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::scoreboard::{ScoreboardController, SubstitutionKind};
+
+    #[tokio::test]
+    async fn test_report_has_scores_and_timeline() {
+        let controller = ScoreboardController::new(true, Config::default());
+        controller.set_teams("Town & Gown".to_string(), "Henley Hawks".to_string()).await.unwrap();
+        controller.set_roster("home", vec![Player { number: 11, name: "John Smith".to_string() }]).await.unwrap();
+        controller.score_action_by("home", "try", Some(11)).await.unwrap();
+        assert!(match_report(&controller.snapshot("main").await).contains("<td>Henley Hawks</td><td class=\"number\">-</td>"));
+        controller.start_timer().await.unwrap();
+        controller.end_period().await.unwrap();
+        controller.start_period().await.unwrap();
+        controller.set_timer(52, 10).await.unwrap();
+        controller.score_action("away", "penalty").await.unwrap();
+        let hia = controller.substitute("away", SubstitutionKind::Hia, 12, 22).await.unwrap();
+        controller.end_replacement(hia.id, true).await.unwrap();
+        controller.give_card("home", "yellow", 11).await.unwrap();
+
+        let report = match_report(&controller.snapshot("main").await);
+        assert!(report.contains("<h1>Town &amp; Gown v Henley Hawks</h1>"));
+        assert!(report.contains("<td>Henley Hawks</td><td class=\"number\">0</td><td class=\"number\">3</td>"));
+        assert!(report.contains("<td class=\"number\">53'</td><td>Henley Hawks</td><td>Penalty</td><td></td>"));
+        assert!(report.contains("<tr><td>11 John Smith</td><td></td></tr>"));
+        assert!(report.contains("<td>HIA</td><td>12</td><td>22</td><td>Returned (53')</td>"));
+        assert!(report.contains("<td>Town &amp; Gown</td><td>Yellow</td><td>11 John Smith</td>"));
+    }
+}
@@ -0,0 +1,265 @@
+//! End-to-end tests driving the web API against a mock control card
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use scoreboard_server::config::{Config, LeagueConfig};
+use scoreboard_server::mock::MockCard;
+use scoreboard_server::protocol::{Color, windows};
+use scoreboard_server::scoreboard::Pitches;
+use scoreboard_server::teams::TeamRegistry;
+use scoreboard_server::fixtures::FixtureStore;
+use scoreboard_server::league::LeagueTable;
+use scoreboard_server::archive::MatchArchive;
+use scoreboard_server::web;
+use warp::{Filter, Rejection, Reply};
+
+const WAIT: Duration = Duration::from_secs(5);
+
+/// Routes with empty stores in files unique to the test
+fn routes(pitches: Pitches, name: &str) -> impl Filter<Extract = impl Reply, Error = Rejection> + Clone {
+    let path = |store: &str| {
+        let path = std::env::temp_dir().join(format!("{}-{}-{}.yaml", store, name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    };
+    let teams = Arc::new(TeamRegistry::load(path("teams")));
+    let fixtures = Arc::new(FixtureStore::load(path("fixtures")));
+    let league = Arc::new(LeagueTable::load(path("league"), LeagueConfig::default()));
+    let archive = Arc::new(MatchArchive::load(path("matches")).unwrap());
+    web::create_routes(pitches, teams, fixtures, league, archive)
+}
+
+/// Start a mock card and a server pointed at it
+async fn setup() -> (MockCard, Pitches) {
+    let card = MockCard::new(1);
+    let address = card.start("127.0.0.1:0").await.unwrap();
+
+    let mut config = Config::default();
+    config.scoreboard.address = address.to_string();
+    config.scoreboard.card_id = 1;
+
+    let sponsorship = std::env::temp_dir().join(format!("sponsorship-{}", std::process::id()));
+    let pitches = Pitches::new(false, &config, sponsorship);
+    pitches.default_pitch().controller.initialize().await.unwrap();
+    assert!(card.wait_for_text(windows::HOME_NAME, "HOME", WAIT).await);
+    (card, pitches)
+}
+
+#[tokio::test]
+async fn test_try_shows_on_board() {
+    let (card, pitches) = setup().await;
+    let routes = routes(pitches, "try");
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/api/score/action")
+        .json(&serde_json::json!({ "team": "home", "action": "try" }))
+        .reply(&routes)
+        .await;
+    assert_eq!(response.status(), 200);
+
+    assert!(card.wait_for_text(windows::HOME_SCORE, "5", WAIT).await);
+    assert_eq!(card.text(windows::AWAY_SCORE).await.as_deref(), Some("0"));
+
+    let display = card.display();
+    let display = display.lock().await;
+    assert!(display.errors.is_empty(), "{:?}", display.errors);
+}
+
+#[tokio::test]
+async fn test_team_names_show_on_board() {
+    let (card, pitches) = setup().await;
+    let routes = routes(pitches, "names");
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/api/teams")
+        .json(&serde_json::json!({ "home_team": "HRUFC", "away_team": "VISITORS" }))
+        .reply(&routes)
+        .await;
+    assert_eq!(response.status(), 200);
+
+    assert!(card.wait_for_text(windows::HOME_NAME, "HRUFC", WAIT).await);
+    assert!(card.wait_for_text(windows::AWAY_NAME, "VISITORS", WAIT).await);
+}
+
+#[tokio::test]
+async fn test_selected_teams_show_in_their_colours() {
+    let (card, pitches) = setup().await;
+    let routes = routes(pitches, "select");
+
+    let mut ids = Vec::new();
+    for (name, color) in [("HRUFC", "#ff0000"), ("VISITORS", "#0000ff")] {
+        let response = warp::test::request()
+            .method("POST")
+            .path("/api/teams/registry")
+            .json(&serde_json::json!({ "name": name, "primary_color": color }))
+            .reply(&routes)
+            .await;
+        let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+        ids.push(body["data"]["id"].as_str().unwrap().to_string());
+    }
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/api/teams/select")
+        .json(&serde_json::json!({ "home_id": ids[0], "away_id": ids[1] }))
+        .reply(&routes)
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["success"], true);
+
+    assert!(card.wait_for_text(windows::HOME_NAME, "HRUFC", WAIT).await);
+    assert!(card.wait_for_text(windows::AWAY_NAME, "VISITORS", WAIT).await);
+    let display = card.display();
+    let display = display.lock().await;
+    assert_eq!(display.windows[windows::HOME_NAME as usize].color, Color::RED);
+    assert_eq!(display.windows[windows::AWAY_NAME as usize].color, Color::BLUE);
+}
+
+#[tokio::test]
+async fn test_match_set_up_from_imported_fixture() {
+    let (card, pitches) = setup().await;
+    let controller = pitches.default_pitch().controller.clone();
+    let routes = routes(pitches, "fixture");
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/api/fixtures/import?format=csv")
+        .body("date,time,home,away,period_minutes\n18/10/2099,14:30,HRUFC,VISITORS,35\n")
+        .reply(&routes)
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["data"]["imported"], 1);
+
+    let response = warp::test::request().path("/api/fixtures").reply(&routes).await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    let id = body["data"][0]["id"].as_str().unwrap().to_string();
+
+    controller.set_scores(10, 3).await.unwrap();
+    let response = warp::test::request()
+        .method("POST")
+        .path(&format!("/api/fixtures/{}/setup", id))
+        .reply(&routes)
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["success"], true);
+    assert_eq!(body["data"]["period_minutes"], 35);
+    assert_eq!(body["data"]["kick_off"], "2099-10-18T14:30:00");
+
+    assert!(card.wait_for_text(windows::HOME_NAME, "HRUFC", WAIT).await);
+    assert!(card.wait_for_text(windows::AWAY_NAME, "VISITORS", WAIT).await);
+    assert!(card.wait_for_text(windows::HOME_SCORE, "0", WAIT).await);
+}
+
+#[tokio::test]
+async fn test_finished_match_is_archived_and_exported() {
+    let (card, pitches) = setup().await;
+    let routes = routes(pitches, "finish");
+
+    for team in ["home", "home", "away"] {
+        warp::test::request()
+            .method("POST")
+            .path("/api/score/action")
+            .json(&serde_json::json!({ "team": team, "action": "try" }))
+            .reply(&routes)
+            .await;
+    }
+    let response = warp::test::request().method("POST").path("/api/match/finish").reply(&routes).await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["success"], true);
+    let id = body["data"]["id"].as_str().unwrap().to_string();
+
+    // The final score stays on the board
+    assert!(card.wait_for_text(windows::HOME_SCORE, "10", WAIT).await);
+
+    let response = warp::test::request().path("/api/matches").reply(&routes).await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["data"][0]["home_score"], 10);
+
+    let response = warp::test::request().path(&format!("/api/matches/{}", id)).reply(&routes).await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["data"]["events"].as_array().unwrap().len(), 3);
+
+    let response = warp::test::request().path("/api/matches/export?format=csv").reply(&routes).await;
+    assert_eq!(response.headers()["content-type"], "text/csv");
+    let csv = String::from_utf8(response.body().to_vec()).unwrap();
+    assert!(csv.lines().nth(1).unwrap().contains(",HOME,AWAY,10,5,2,1,"));
+
+    let response = warp::test::request().path("/api/league/table").reply(&routes).await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["data"][0]["team"], "HOME");
+    assert_eq!(body["data"][0]["points"], 4);
+}
+
+#[tokio::test]
+async fn test_full_screen_message_then_scores_come_back() {
+    let (card, pitches) = setup().await;
+    let routes = routes(pitches, "message");
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/api/display/message")
+        .json(&serde_json::json!({ "text": "RAFFLE 42", "duration": 30, "fullscreen": true, "effect": "flicker" }))
+        .reply(&routes)
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["success"], true, "{}", body);
+
+    // The whole board is a single window showing the message
+    assert!(card.wait_for_text(0, "RAFFLE 42", WAIT).await);
+    assert_eq!(card.text(windows::TIMER).await, None);
+
+    let response = warp::test::request()
+        .method("DELETE")
+        .path(&format!("/api/display/messages/{}", body["data"]["id"]))
+        .reply(&routes)
+        .await;
+    assert_eq!(response.status(), 200);
+    assert!(card.wait_for_text(windows::TIMER, "00:00", WAIT).await);
+    assert_eq!(card.text(windows::HOME_NAME).await.as_deref(), Some("HOME"));
+}
+
+#[tokio::test]
+async fn test_uploaded_image_is_fitted_to_its_window() {
+    let (card, pitches) = setup().await;
+    let routes = routes(pitches, "image");
+
+    // A 64 x 16 red crest, twice as wide as the home score window
+    let mut crest = Vec::new();
+    let mut encoder = png::Encoder::new(&mut crest, 64, 16);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.write_header().unwrap().write_image_data(&[0xff, 0x00, 0x00].repeat(64 * 16)).unwrap();
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/api/display/image?window=home_score&name=crest&duration=30")
+        .body(crest)
+        .reply(&routes)
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["success"], true, "{}", body);
+
+    let mut image = None;
+    for _ in 0..500 {
+        image = card.image(windows::HOME_SCORE).await;
+        if image.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let image = image.expect("no image on the board");
+    assert_eq!((image.x, image.y, image.picture.width, image.picture.height), (0, 4, 32, 8));
+    assert!(image.picture.pixels.iter().all(|pixel| *pixel == Color::RED));
+    assert_eq!(card.text(windows::HOME_NAME).await.as_deref(), Some("HOME"));
+
+    let response = warp::test::request()
+        .method("POST")
+        .path("/api/display/image")
+        .body("not an image")
+        .reply(&routes)
+        .await;
+    let body: serde_json::Value = serde_json::from_slice(response.body()).unwrap();
+    assert_eq!(body["success"], false);
+}